@@ -1,6 +1,5 @@
-use crate::{mesh::algo::location::face, prelude::*};
+use crate::prelude::*;
 use bevy_math::Vec3;
-use core::panic;
 use std::collections::HashMap;
 
 #[derive(Default)]
@@ -65,6 +64,15 @@ where
 {
     #[must_use]
     pub fn bevy(&self, color_map: &HashMap<FaceKey<M>, [f32; 3]>) -> (bevy_render::mesh::Mesh, Vector3D, f64) {
+        let mut attributes = Attributes::default();
+        self.cache_vertex_normals(NormalWeighting::default(), &mut attributes);
+        self.bevy_with_attributes(color_map, &attributes)
+    }
+
+    // Same as `Mesh::bevy`, with the vertex normals cached in the attributes (see `Mesh::cache_vertex_normals`), if any.
+    #[must_use]
+    pub fn bevy_with_attributes(&self, color_map: &HashMap<FaceKey<M>, [f32; 3]>, attributes: &Attributes<M>) -> (bevy_render::mesh::Mesh, Vector3D, f64) {
+        let normals = attributes.vertex_normals();
         if self.faces.is_empty() {
             return (MeshBuilder::with_capacity(0).build(), Vector3D::new(0., 0., 0.), 1.);
         }
@@ -89,7 +97,10 @@ where
             for vertex_id in triangle {
                 bevy_mesh_builder.add_vertex(
                     &triangle_mesh.position(vertex_id),
-                    &triangle_mesh.normal(vertex_id),
+                    &normals
+                        .and_then(|normals| normals.get(vertex_id))
+                        .copied()
+                        .unwrap_or_else(|| self.normal(vertex_id)),
                     color_map.get(&original_face).unwrap_or(&[0., 0., 0.]),
                 );
             }
@@ -118,8 +129,13 @@ where
 
     #[must_use]
     pub fn scale_translation(&self) -> (f64, Vector3D) {
+        self.scale_translation_around(self.center())
+    }
+
+    // Same as `Mesh::scale_translation`, but centered on the given point (for example `Mesh::volume_centroid`) instead of the center of the bounding box.
+    #[must_use]
+    pub fn scale_translation_around(&self, center: Vector3D) -> (f64, Vector3D) {
         let scale = 20. * (1. / self.max_dim());
-        let center = self.center();
         (scale, -scale * center)
     }
 }
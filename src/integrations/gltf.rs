@@ -0,0 +1,248 @@
+use crate::prelude::*;
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+
+// Colors to attach to an exported glTF mesh.
+// Vertices are exploded per triangle corner (as in `Mesh::bevy`), so both per-vertex and per-face colors are supported.
+// If no color is assigned to an element, it will default to black.
+#[derive(Default, Clone, Copy, Debug)]
+pub enum GltfColors<'a, M: Tag> {
+    #[default]
+    None,
+    PerVertex(&'a HashMap<VertKey<M>, Color>),
+    PerFace(&'a HashMap<FaceKey<M>, Color>),
+}
+
+impl<M: Tag> Mesh<M>
+where
+    M: std::default::Default + std::cmp::Eq + std::hash::Hash + Copy + Clone,
+{
+    // Read all static triangle primitives of the default scene (or all scenes if there is none), with node transforms applied.
+    // Vertices with identical (transformed) positions are welded, as glTF exporters usually duplicate corners.
+    // The returned vertex map refers to the welded vertex indices, the face map to the triangles in traversal order.
+    pub fn from_gltf(path: &PathBuf) -> Result<MeshWithIds<M>, MeshError<M>> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("gltf" | "glb") => match ::gltf::import(path) {
+                Ok((document, buffers, _)) => {
+                    let (verts, faces) = Self::gltf_to_elements(&document, &buffers);
+                    Self::from(&faces, &verts)
+                }
+                Err(e) => Err(MeshError::Unknown(format!(
+                    "Something went wrong while reading the glTF file: {path:?}\nErr: {e}"
+                ))),
+            },
+            _ => Err(MeshError::Unknown(format!("Unknown file extension: {path:?}",))),
+        }
+    }
+
+    // Write the mesh as glTF 2.0. A `.glb` extension gives a single binary file, otherwise a `.gltf` file is written next to a `.bin` buffer.
+    // Faces are triangulated and every triangle corner gets its own vertex (with position, normal, color and optionally uv).
+    pub fn to_gltf(&self, path: &PathBuf, colors: GltfColors<M>, uvs: Option<&HashMap<VertKey<M>, [f32; 2]>>) -> Result<(), std::io::Error> {
+        let (triangle_mesh, face_map) = self.triangulate().map_err(|e| std::io::Error::other(format!("{e:?}")))?;
+
+        let mut positions = vec![];
+        let mut normals = vec![];
+        let mut vertex_colors = vec![];
+        let mut vertex_uvs = vec![];
+        for face_id in triangle_mesh.face_ids() {
+            let original_face = face_map.get(&face_id).copied().unwrap_or(face_id);
+            for vertex_id in triangle_mesh.vertices(face_id) {
+                positions.push(to_f32(triangle_mesh.position(vertex_id)));
+                normals.push(to_f32(triangle_mesh.normal(vertex_id)));
+                match colors {
+                    GltfColors::None => {}
+                    GltfColors::PerVertex(color_map) => vertex_colors.push(*color_map.get(&vertex_id).unwrap_or(&[0., 0., 0.])),
+                    GltfColors::PerFace(color_map) => vertex_colors.push(*color_map.get(&original_face).unwrap_or(&[0., 0., 0.])),
+                }
+                if let Some(uv_map) = uvs {
+                    vertex_uvs.push(*uv_map.get(&vertex_id).unwrap_or(&[0., 0.]));
+                }
+            }
+        }
+        let indices = (0..u32::try_from(positions.len()).map_err(std::io::Error::other)?).collect_vec();
+
+        // Lay out all attributes (and the indices) consecutively in a single buffer.
+        let mut buffer = GltfBuffer::default();
+        let mut attributes = serde_json::Map::new();
+        let (min, max) = positions.iter().fold(([f32::MAX; 3], [f32::MIN; 3]), |(min, max), p| {
+            (
+                [min[0].min(p[0]), min[1].min(p[1]), min[2].min(p[2])],
+                [max[0].max(p[0]), max[1].max(p[1]), max[2].max(p[2])],
+            )
+        });
+        let accessor = buffer.push(positions.iter().flatten(), positions.len(), "VEC3", Some((min.to_vec(), max.to_vec())));
+        attributes.insert("POSITION".to_owned(), accessor.into());
+        let accessor = buffer.push(normals.iter().flatten(), normals.len(), "VEC3", None);
+        attributes.insert("NORMAL".to_owned(), accessor.into());
+        if !vertex_colors.is_empty() {
+            let accessor = buffer.push(vertex_colors.iter().flatten(), vertex_colors.len(), "VEC3", None);
+            attributes.insert("COLOR_0".to_owned(), accessor.into());
+        }
+        if !vertex_uvs.is_empty() {
+            let accessor = buffer.push(vertex_uvs.iter().flatten(), vertex_uvs.len(), "VEC2", None);
+            attributes.insert("TEXCOORD_0".to_owned(), accessor.into());
+        }
+        let indices_accessor = buffer.push_indices(&indices);
+
+        let binary = path.extension().and_then(|ext| ext.to_str()) == Some("glb");
+        let bin_path = path.with_extension("bin");
+        let uri = if binary {
+            None
+        } else {
+            bin_path.file_name().and_then(|name| name.to_str()).map(str::to_owned)
+        };
+
+        let mut json_buffer = serde_json::json!({ "byteLength": buffer.data.len() });
+        if let Some(uri) = uri {
+            json_buffer["uri"] = uri.into();
+        }
+        let json = serde_json::json!({
+            "asset": { "version": "2.0", "generator": "mehsh" },
+            "scene": 0,
+            "scenes": [{ "nodes": [0] }],
+            "nodes": [{ "mesh": 0 }],
+            "meshes": [{ "primitives": [{ "attributes": attributes, "indices": indices_accessor, "mode": 4 }] }],
+            "buffers": [json_buffer],
+            "bufferViews": buffer.views,
+            "accessors": buffer.accessors,
+        })
+        .to_string();
+
+        if binary {
+            // GLB container: 12-byte header, followed by a JSON chunk (padded with spaces) and a BIN chunk (padded with zeros).
+            let mut json_chunk = json.into_bytes();
+            json_chunk.resize(json_chunk.len().next_multiple_of(4), b' ');
+            let mut bin_chunk = buffer.data;
+            bin_chunk.resize(bin_chunk.len().next_multiple_of(4), 0);
+            let total_length = 12 + 8 + json_chunk.len() + 8 + bin_chunk.len();
+
+            let mut file = std::fs::File::create(path)?;
+            file.write_all(b"glTF")?;
+            file.write_all(&2u32.to_le_bytes())?;
+            file.write_all(&u32::try_from(total_length).map_err(std::io::Error::other)?.to_le_bytes())?;
+            file.write_all(&u32::try_from(json_chunk.len()).map_err(std::io::Error::other)?.to_le_bytes())?;
+            file.write_all(b"JSON")?;
+            file.write_all(&json_chunk)?;
+            file.write_all(&u32::try_from(bin_chunk.len()).map_err(std::io::Error::other)?.to_le_bytes())?;
+            file.write_all(b"BIN\0")?;
+            file.write_all(&bin_chunk)?;
+        } else {
+            std::fs::write(path, json)?;
+            std::fs::write(bin_path, buffer.data)?;
+        }
+
+        Ok(())
+    }
+
+    fn gltf_to_elements(document: &::gltf::Document, buffers: &[::gltf::buffer::Data]) -> (Vec<Vector3D>, Vec<Vec<usize>>) {
+        let mut verts = vec![];
+        let mut faces = vec![];
+        let mut welded = HashMap::<[u64; 3], usize>::new();
+
+        let roots = document.default_scene().map_or_else(
+            || document.scenes().flat_map(|scene| scene.nodes()).collect_vec(),
+            |scene| scene.nodes().collect_vec(),
+        );
+        let mut stack = roots.into_iter().map(|node| (node, nalgebra::Matrix4::<f64>::identity())).collect_vec();
+
+        while let Some((node, parent_transform)) = stack.pop() {
+            let local = node.transform().matrix();
+            let transform = parent_transform * nalgebra::Matrix4::from_fn(|row, col| f64::from(local[col][row]));
+            stack.extend(node.children().map(|child| (child, transform)));
+
+            let Some(mesh) = node.mesh() else {
+                continue;
+            };
+            for primitive in mesh.primitives() {
+                let reader = primitive.reader(|buffer| buffers.get(buffer.index()).map(|data| &data[..]));
+                let Some(positions) = reader.read_positions() else {
+                    continue;
+                };
+
+                // Map every primitive vertex to a welded vertex index.
+                let indices = positions
+                    .map(|p| {
+                        let p = transform.transform_point(&nalgebra::Point3::new(f64::from(p[0]), f64::from(p[1]), f64::from(p[2])));
+                        *welded.entry([p.x.to_bits(), p.y.to_bits(), p.z.to_bits()]).or_insert_with(|| {
+                            verts.push(p.coords);
+                            verts.len() - 1
+                        })
+                    })
+                    .collect_vec();
+                let order = reader
+                    .read_indices()
+                    .map_or_else(|| (0..indices.len()).collect_vec(), |i| i.into_u32().map(|i| i as usize).collect_vec());
+
+                let triangles = match primitive.mode() {
+                    ::gltf::mesh::Mode::Triangles => order.chunks_exact(3).map(|t| [t[0], t[1], t[2]]).collect_vec(),
+                    ::gltf::mesh::Mode::TriangleStrip => order
+                        .windows(3)
+                        .enumerate()
+                        .map(|(i, t)| if i % 2 == 0 { [t[0], t[1], t[2]] } else { [t[1], t[0], t[2]] })
+                        .collect_vec(),
+                    ::gltf::mesh::Mode::TriangleFan => order.windows(2).skip(1).map(|t| [order[0], t[0], t[1]]).collect_vec(),
+                    _ => continue,
+                };
+
+                // Flip the winding if the transform mirrors the geometry.
+                let mirrored = transform.fixed_view::<3, 3>(0, 0).determinant() < 0.;
+                for triangle in triangles {
+                    let [a, b, c] = triangle.map(|i| indices[i]);
+                    if a == b || b == c || c == a {
+                        continue;
+                    }
+                    faces.push(if mirrored { vec![a, c, b] } else { vec![a, b, c] });
+                }
+            }
+        }
+
+        (verts, faces)
+    }
+}
+
+// Binary buffer with its buffer views and accessors, as they are written to the glTF JSON.
+#[derive(Default)]
+struct GltfBuffer {
+    data: Vec<u8>,
+    views: Vec<serde_json::Value>,
+    accessors: Vec<serde_json::Value>,
+}
+
+impl GltfBuffer {
+    // Append floats as a new buffer view and accessor. Returns the index of the accessor.
+    fn push<'a>(&mut self, values: impl Iterator<Item = &'a f32>, count: usize, kind: &str, bounds: Option<(Vec<f32>, Vec<f32>)>) -> usize {
+        let offset = self.data.len();
+        for value in values {
+            self.data.extend_from_slice(&value.to_le_bytes());
+        }
+        self.views
+            .push(serde_json::json!({ "buffer": 0, "byteOffset": offset, "byteLength": self.data.len() - offset, "target": 34962 }));
+        let mut accessor = serde_json::json!({ "bufferView": self.views.len() - 1, "componentType": 5126, "count": count, "type": kind });
+        if let Some((min, max)) = bounds {
+            accessor["min"] = min.into();
+            accessor["max"] = max.into();
+        }
+        self.accessors.push(accessor);
+        self.accessors.len() - 1
+    }
+
+    // Append triangle indices as a new buffer view and accessor. Returns the index of the accessor.
+    fn push_indices(&mut self, indices: &[u32]) -> usize {
+        let offset = self.data.len();
+        for index in indices {
+            self.data.extend_from_slice(&index.to_le_bytes());
+        }
+        self.views
+            .push(serde_json::json!({ "buffer": 0, "byteOffset": offset, "byteLength": self.data.len() - offset, "target": 34963 }));
+        self.accessors
+            .push(serde_json::json!({ "bufferView": self.views.len() - 1, "componentType": 5125, "count": indices.len(), "type": "SCALAR" }));
+        self.accessors.len() - 1
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn to_f32(vec: Vector3D) -> [f32; 3] {
+    [vec.x as f32, vec.y as f32, vec.z as f32]
+}
@@ -0,0 +1,262 @@
+use crate::prelude::*;
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+
+// Elements of a Gmsh file that are relevant for the surface, with 0-based vertex indices.
+#[derive(Default)]
+struct GmshElements {
+    verts: Vec<Vector3D>,
+    faces: Vec<(Vec<usize>, i64)>,
+    ridges: Vec<[usize; 2]>,
+    corners: Vec<(usize, i64)>,
+}
+
+impl<M: Tag> Mesh<M>
+where
+    M: std::default::Default + std::cmp::Eq + std::hash::Hash + Copy + Clone,
+{
+    // Read the surface part of an ASCII Gmsh file (version 2.2 or 4.1).
+    // Triangles and quadrangles (higher-order elements are reduced to their corners) become faces, labeled by their physical tag.
+    // If an element has no physical tag, its elementary (entity) tag is used instead.
+    // Line elements are mapped to ridges, point elements to corners (with their tag as vertex label). Volume elements are skipped.
+    pub fn from_gmsh(path: &PathBuf) -> Result<LabeledMeshWithIds<M>, MeshError<M>> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => match path.extension().and_then(|ext| ext.to_str()) {
+                Some("msh") => match Self::gmsh_to_elements(&contents) {
+                    Ok(elements) => {
+                        let faces = elements.faces.iter().map(|(face, _)| face.clone()).collect_vec();
+                        let (mesh, vert_map, face_map) = Self::from(&faces, &elements.verts)?;
+
+                        let mut labels = Labels::new();
+                        for (i, &(_, tag)) in elements.faces.iter().enumerate() {
+                            if let Some(&face_id) = face_map.key(i) {
+                                labels.faces.insert(face_id, tag);
+                            }
+                        }
+                        for &[a, b] in &elements.ridges {
+                            if let (Some(&a), Some(&b)) = (vert_map.key(a), vert_map.key(b)) {
+                                labels.add_ridge(&mesh, a, b);
+                            }
+                        }
+                        for &(corner, tag) in &elements.corners {
+                            if let Some(&vert_id) = vert_map.key(corner) {
+                                labels.corners.insert(vert_id);
+                                labels.verts.insert(vert_id, tag);
+                            }
+                        }
+
+                        Ok((mesh, vert_map, face_map, labels))
+                    }
+                    Err(e) => Err(MeshError::Unknown(format!(
+                        "Something went wrong while reading the Gmsh file: {path:?}\nErr: {e}"
+                    ))),
+                },
+                _ => Err(MeshError::Unknown(format!("Unknown file extension: {path:?}",))),
+            },
+            Err(e) => Err(MeshError::Unknown(format!("Cannot read file: {path:?}\nErr: {e}"))),
+        }
+    }
+
+    // Write the mesh as an ASCII Gmsh file (version 2.2). Faces are written as triangles and quadrangles (other polygons are not supported).
+    // Face labels are written as physical and elementary tags, ridges as line elements and corners as point elements.
+    pub fn to_gmsh(&self, path: &PathBuf, labels: &Labels<M>) -> Result<(), std::io::Error> {
+        if let Some(face_id) = self.face_ids().into_iter().find(|&face_id| !(3..=4).contains(&self.vertices(face_id).len())) {
+            return Err(std::io::Error::other(format!("{face_id:?} is neither a triangle nor a quadrangle")));
+        }
+
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+
+        let mut vert_ids = ids::IdMap::<VERT, M>::new();
+        for (i, vert_id) in self.vert_ids().into_iter().enumerate() {
+            vert_ids.insert(i + 1, vert_id);
+        }
+
+        writeln!(file, "$MeshFormat")?;
+        writeln!(file, "2.2 0 8")?;
+        writeln!(file, "$EndMeshFormat")?;
+
+        writeln!(file, "$Nodes")?;
+        writeln!(file, "{}", self.nr_verts())?;
+        for vert_id in self.vert_ids() {
+            let p = self.position(vert_id);
+            writeln!(file, "{} {} {} {}", vert_ids.id(&vert_id).unwrap(), p.x, p.y, p.z)?;
+        }
+        writeln!(file, "$EndNodes")?;
+
+        // Element types: 15 = point, 1 = line, 2 = triangle, 3 = quadrangle.
+        let mut elements = vec![];
+        for vert_id in self.vert_ids().into_iter().filter(|vert_id| labels.corners.contains(vert_id)) {
+            elements.push((15, labels.verts.get(vert_id).copied().unwrap_or_default(), vec![vert_id]));
+        }
+        for edge_id in labels.unique_ridges(self) {
+            elements.push((1, 0, self.vertices(edge_id)));
+        }
+        for face_id in self.face_ids() {
+            let corners = self.vertices(face_id);
+            elements.push((
+                if corners.len() == 3 { 2 } else { 3 },
+                labels.faces.get(face_id).copied().unwrap_or_default(),
+                corners,
+            ));
+        }
+
+        writeln!(file, "$Elements")?;
+        writeln!(file, "{}", elements.len())?;
+        for (i, (element_type, tag, corners)) in elements.into_iter().enumerate() {
+            let nodes = corners.iter().map(|vert_id| vert_ids.id(vert_id).unwrap()).join(" ");
+            writeln!(file, "{} {element_type} 2 {tag} {tag} {nodes}", i + 1)?;
+        }
+        writeln!(file, "$EndElements")?;
+
+        file.flush()
+    }
+
+    fn gmsh_to_elements(contents: &str) -> Result<GmshElements, String> {
+        let mut lines = contents.lines().map(str::trim).filter(|line| !line.is_empty());
+        let mut elements = GmshElements::default();
+
+        let mut version = 2;
+        // Node tags to 0-based vertex indices.
+        let mut nodes = HashMap::<usize, usize>::new();
+        // Physical tags of the entities (by dimension and entity tag), version 4 only.
+        let mut physicals = HashMap::<(usize, i64), Vec<i64>>::new();
+
+        while let Some(section) = lines.next() {
+            match section {
+                "$MeshFormat" => {
+                    let header = next_line(&mut lines)?;
+                    match header.split_whitespace().collect_vec()[..] {
+                        [v, "0", ..] if v.starts_with("2.") => version = 2,
+                        [v, "0", ..] if v.starts_with("4.") => version = 4,
+                        [v, "0", ..] => return Err(format!("Unsupported version: {v}")),
+                        _ => return Err("Only ASCII files are supported".to_owned()),
+                    }
+                }
+                "$Entities" => {
+                    let counts = parse_all::<usize>(next_line(&mut lines)?)?;
+                    for (dim, &count) in counts.iter().enumerate().take(4) {
+                        for _ in 0..count {
+                            let entity = next_line(&mut lines)?.split_whitespace().collect_vec();
+                            // Points have a position (3 values), other entities have a bounding box (6 values).
+                            let offset = if dim == 0 { 4 } else { 7 };
+                            let tag = parse::<i64>(entity.first().copied())?;
+                            let nr_physicals = parse::<usize>(entity.get(offset).copied())?;
+                            let tags = (0..nr_physicals)
+                                .map(|i| parse::<i64>(entity.get(offset + 1 + i).copied()))
+                                .collect::<Result<_, _>>()?;
+                            physicals.insert((dim, tag), tags);
+                        }
+                    }
+                }
+                "$Nodes" if version == 2 => {
+                    for _ in 0..parse::<usize>(Some(next_line(&mut lines)?))? {
+                        let mut node = next_line(&mut lines)?.split_whitespace();
+                        nodes.insert(parse(node.next())?, elements.verts.len());
+                        elements
+                            .verts
+                            .push(Vector3D::new(parse(node.next())?, parse(node.next())?, parse(node.next())?));
+                    }
+                }
+                "$Nodes" => {
+                    let blocks = parse::<usize>(next_line(&mut lines)?.split_whitespace().next())?;
+                    for _ in 0..blocks {
+                        let header = parse_all::<usize>(next_line(&mut lines)?)?;
+                        let &[_, _, _, count] = &header[..] else {
+                            return Err("Invalid node block".to_owned());
+                        };
+                        let tags = (0..count)
+                            .map(|_| parse::<usize>(Some(next_line(&mut lines)?)))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        for tag in tags {
+                            let mut node = next_line(&mut lines)?.split_whitespace();
+                            nodes.insert(tag, elements.verts.len());
+                            elements
+                                .verts
+                                .push(Vector3D::new(parse(node.next())?, parse(node.next())?, parse(node.next())?));
+                        }
+                    }
+                }
+                "$Elements" if version == 2 => {
+                    for _ in 0..parse::<usize>(Some(next_line(&mut lines)?))? {
+                        let element = parse_all::<i64>(next_line(&mut lines)?)?;
+                        let &[_, element_type, nr_tags, ..] = &element[..] else {
+                            return Err("Invalid element".to_owned());
+                        };
+                        let nr_tags = usize::try_from(nr_tags).map_err(|_| "Invalid element".to_owned())?;
+                        let tags = element.get(3..3 + nr_tags).ok_or("Invalid element")?;
+                        let tag = tags.iter().copied().find(|&tag| tag != 0).unwrap_or_default();
+                        add_element(&mut elements, &nodes, element_type, tag, element.get(3 + nr_tags..).unwrap_or_default())?;
+                    }
+                }
+                "$Elements" => {
+                    let blocks = parse::<usize>(next_line(&mut lines)?.split_whitespace().next())?;
+                    for _ in 0..blocks {
+                        let header = parse_all::<i64>(next_line(&mut lines)?)?;
+                        let &[dim, entity, element_type, count] = &header[..] else {
+                            return Err("Invalid element block".to_owned());
+                        };
+                        let dim = usize::try_from(dim).map_err(|_| "Invalid element block".to_owned())?;
+                        let tag = physicals.get(&(dim, entity)).and_then(|tags| tags.first().copied()).unwrap_or(entity);
+                        for _ in 0..count {
+                            let element = parse_all::<i64>(next_line(&mut lines)?)?;
+                            add_element(&mut elements, &nodes, element_type, tag, element.get(1..).unwrap_or_default())?;
+                        }
+                    }
+                }
+                _ => {}
+            }
+
+            // Skip (the remainder of) the section.
+            if let Some(name) = section.strip_prefix('$') {
+                let end = format!("$End{name}");
+                lines.by_ref().find(|&line| line == end).ok_or(format!("Missing {end}"))?;
+            }
+        }
+
+        Ok(elements)
+    }
+}
+
+// Add a Gmsh element (given by its type, tag and node tags) to the surface elements.
+fn add_element(elements: &mut GmshElements, nodes: &HashMap<usize, usize>, element_type: i64, tag: i64, node_tags: &[i64]) -> Result<(), String> {
+    // Number of corners of the (linear or higher-order) points, lines, triangles and quadrangles.
+    let corners = match element_type {
+        15 => 1,
+        1 | 8 => 2,
+        2 | 9 | 20 | 21 => 3,
+        3 | 10 | 16 => 4,
+        _ => return Ok(()),
+    };
+    let verts = node_tags
+        .get(..corners)
+        .ok_or("Element has too few nodes")?
+        .iter()
+        .map(|&node| {
+            usize::try_from(node)
+                .ok()
+                .and_then(|node| nodes.get(&node).copied())
+                .ok_or(format!("Node {node} does not exist"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    match corners {
+        1 => elements.corners.push((verts[0], tag)),
+        2 => elements.ridges.push([verts[0], verts[1]]),
+        _ => elements.faces.push((verts, tag)),
+    }
+    Ok(())
+}
+
+fn next_line<'a>(lines: &mut impl Iterator<Item = &'a str>) -> Result<&'a str, String> {
+    lines.next().ok_or_else(|| "Unexpected end of file".to_owned())
+}
+
+fn parse<T: std::str::FromStr>(token: Option<&str>) -> Result<T, String> {
+    let token = token.ok_or("Unexpected end of line")?;
+    token.parse().map_err(|_| format!("Cannot parse: {token}"))
+}
+
+fn parse_all<T: std::str::FromStr>(line: &str) -> Result<Vec<T>, String> {
+    line.split_whitespace().map(|token| parse(Some(token))).collect()
+}
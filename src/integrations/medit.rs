@@ -0,0 +1,235 @@
+use crate::prelude::*;
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+
+// Elements of a Medit file that are relevant for the surface, with 0-based vertex indices.
+#[derive(Default)]
+struct MeditElements {
+    verts: Vec<(Vector3D, i64)>,
+    faces: Vec<(Vec<usize>, i64)>,
+    edges: Vec<[usize; 2]>,
+    ridges: Vec<usize>,
+    corners: Vec<usize>,
+}
+
+impl<M: Tag> Mesh<M>
+where
+    M: std::default::Default + std::cmp::Eq + std::hash::Hash + Copy + Clone,
+{
+    // Read the surface part (triangles and quadrilaterals) of an ASCII Medit file.
+    // References are mapped to vertex and face labels, `Ridges` and `Corners` to edge and vertex flags.
+    // Volume elements are skipped.
+    pub fn from_medit(path: &PathBuf) -> Result<LabeledMeshWithIds<M>, MeshError<M>> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => match path.extension().and_then(|ext| ext.to_str()) {
+                Some("mesh") => match Self::medit_to_elements(&contents) {
+                    Ok(elements) => {
+                        let positions = elements.verts.iter().map(|&(position, _)| position).collect_vec();
+                        let faces = elements.faces.iter().map(|(face, _)| face.clone()).collect_vec();
+                        let (mesh, vert_map, face_map) = Self::from(&faces, &positions)?;
+
+                        let mut labels = Labels::new();
+                        for (i, &(_, reference)) in elements.verts.iter().enumerate() {
+                            if let Some(&vert_id) = vert_map.key(i) {
+                                labels.verts.insert(vert_id, reference);
+                            }
+                        }
+                        for (i, &(_, reference)) in elements.faces.iter().enumerate() {
+                            if let Some(&face_id) = face_map.key(i) {
+                                labels.faces.insert(face_id, reference);
+                            }
+                        }
+                        for &ridge in &elements.ridges {
+                            if let Some(&[a, b]) = elements.edges.get(ridge)
+                                && let (Some(&a), Some(&b)) = (vert_map.key(a), vert_map.key(b))
+                            {
+                                labels.add_ridge(&mesh, a, b);
+                            }
+                        }
+                        for &corner in &elements.corners {
+                            if let Some(&vert_id) = vert_map.key(corner) {
+                                labels.corners.insert(vert_id);
+                            }
+                        }
+
+                        Ok((mesh, vert_map, face_map, labels))
+                    }
+                    Err(e) => Err(MeshError::Unknown(format!(
+                        "Something went wrong while reading the Medit file: {path:?}\nErr: {e}"
+                    ))),
+                },
+                _ => Err(MeshError::Unknown(format!("Unknown file extension: {path:?}",))),
+            },
+            Err(e) => Err(MeshError::Unknown(format!("Cannot read file: {path:?}\nErr: {e}"))),
+        }
+    }
+
+    // Write the mesh as an ASCII Medit file. Faces are written as triangles and quadrilaterals (other polygons are not supported).
+    pub fn to_medit(&self, path: &PathBuf, labels: &Labels<M>) -> Result<(), std::io::Error> {
+        if let Some(face_id) = self.face_ids().into_iter().find(|&face_id| !(3..=4).contains(&self.vertices(face_id).len())) {
+            return Err(std::io::Error::other(format!("{face_id:?} is neither a triangle nor a quadrilateral")));
+        }
+
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+
+        let mut vert_ids = ids::IdMap::<VERT, M>::new();
+        for (i, vert_id) in self.vert_ids().into_iter().enumerate() {
+            vert_ids.insert(i + 1, vert_id);
+        }
+
+        writeln!(file, "MeshVersionFormatted 2")?;
+        writeln!(file, "Dimension 3")?;
+
+        writeln!(file, "Vertices")?;
+        writeln!(file, "{}", self.nr_verts())?;
+        for vert_id in self.vert_ids() {
+            let p = self.position(vert_id);
+            writeln!(file, "{} {} {} {}", p.x, p.y, p.z, labels.verts.get(vert_id).copied().unwrap_or_default())?;
+        }
+
+        for (keyword, degree) in [("Triangles", 3), ("Quadrilaterals", 4)] {
+            let faces = self
+                .face_ids()
+                .into_iter()
+                .filter(|&face_id| self.vertices(face_id).len() == degree)
+                .collect_vec();
+            if faces.is_empty() {
+                continue;
+            }
+            writeln!(file, "{keyword}")?;
+            writeln!(file, "{}", faces.len())?;
+            for face_id in faces {
+                let corners = self.vertices(face_id).iter().map(|vert_id| vert_ids.id(vert_id).unwrap()).join(" ");
+                writeln!(file, "{corners} {}", labels.faces.get(face_id).copied().unwrap_or_default())?;
+            }
+        }
+
+        let ridges = labels.unique_ridges(self);
+        if !ridges.is_empty() {
+            writeln!(file, "Edges")?;
+            writeln!(file, "{}", ridges.len())?;
+            for &edge_id in &ridges {
+                let endpoints = self.vertices(edge_id).iter().map(|vert_id| vert_ids.id(vert_id).unwrap()).join(" ");
+                writeln!(file, "{endpoints} 0")?;
+            }
+            writeln!(file, "Ridges")?;
+            writeln!(file, "{}", ridges.len())?;
+            for i in 1..=ridges.len() {
+                writeln!(file, "{i}")?;
+            }
+        }
+
+        let corners = self.vert_ids().into_iter().filter(|vert_id| labels.corners.contains(vert_id)).collect_vec();
+        if !corners.is_empty() {
+            writeln!(file, "Corners")?;
+            writeln!(file, "{}", corners.len())?;
+            for vert_id in corners {
+                writeln!(file, "{}", vert_ids.id(&vert_id).unwrap())?;
+            }
+        }
+
+        writeln!(file, "End")?;
+        file.flush()
+    }
+
+    fn medit_to_elements(contents: &str) -> Result<MeditElements, String> {
+        let mut tokens = contents.lines().flat_map(|line| line.split('#').next().unwrap_or_default().split_whitespace());
+        let mut elements = MeditElements::default();
+
+        // Number of tokens per entry of the keywords that are skipped.
+        let skipped = HashMap::from([
+            ("Tetrahedra", 5),
+            ("Hexahedra", 9),
+            ("Prisms", 7),
+            ("Pyramids", 6),
+            ("RequiredVertices", 1),
+            ("RequiredEdges", 1),
+            ("RequiredTriangles", 1),
+            ("RequiredQuadrilaterals", 1),
+            ("Normals", 3),
+            ("Tangents", 3),
+            ("NormalAtVertices", 2),
+            ("TangentAtVertices", 2),
+        ]);
+
+        while let Some(keyword) = tokens.next() {
+            match keyword {
+                "MeshVersionFormatted" | "Dimension" => {
+                    let value = next_integer(&mut tokens)?;
+                    if keyword == "Dimension" && value != 3 {
+                        return Err(format!("Unsupported dimension: {value}"));
+                    }
+                }
+                "End" => break,
+                "Vertices" => {
+                    for _ in 0..next_count(&mut tokens)? {
+                        let position = Vector3D::new(next_float(&mut tokens)?, next_float(&mut tokens)?, next_float(&mut tokens)?);
+                        elements.verts.push((position, next_integer(&mut tokens)?));
+                    }
+                }
+                "Triangles" | "Quadrilaterals" => {
+                    let degree = if keyword == "Triangles" { 3 } else { 4 };
+                    for _ in 0..next_count(&mut tokens)? {
+                        let face = (0..degree).map(|_| next_index(&mut tokens)).collect::<Result<Vec<_>, _>>()?;
+                        elements.faces.push((face, next_integer(&mut tokens)?));
+                    }
+                }
+                "Edges" => {
+                    for _ in 0..next_count(&mut tokens)? {
+                        elements.edges.push([next_index(&mut tokens)?, next_index(&mut tokens)?]);
+                        next_integer(&mut tokens)?;
+                    }
+                }
+                "Ridges" => {
+                    for _ in 0..next_count(&mut tokens)? {
+                        elements.ridges.push(next_index(&mut tokens)?);
+                    }
+                }
+                "Corners" => {
+                    for _ in 0..next_count(&mut tokens)? {
+                        elements.corners.push(next_index(&mut tokens)?);
+                    }
+                }
+                _ => match skipped.get(keyword) {
+                    Some(&arity) => {
+                        for _ in 0..next_count(&mut tokens)? * arity {
+                            tokens.next().ok_or("Unexpected end of file")?;
+                        }
+                    }
+                    None => return Err(format!("Unsupported keyword: {keyword}")),
+                },
+            }
+        }
+
+        if let Some(&index) = elements.faces.iter().flat_map(|(face, _)| face).find(|&&index| index >= elements.verts.len()) {
+            return Err(format!("Vertex {} does not exist", index + 1));
+        }
+
+        Ok(elements)
+    }
+}
+
+fn next_integer<'a>(tokens: &mut impl Iterator<Item = &'a str>) -> Result<i64, String> {
+    let token = tokens.next().ok_or("Unexpected end of file")?;
+    token.parse().map_err(|_| format!("Expected an integer, found: {token}"))
+}
+
+fn next_count<'a>(tokens: &mut impl Iterator<Item = &'a str>) -> Result<usize, String> {
+    let token = tokens.next().ok_or("Unexpected end of file")?;
+    token.parse().map_err(|_| format!("Expected a count, found: {token}"))
+}
+
+// Medit indices are 1-based.
+fn next_index<'a>(tokens: &mut impl Iterator<Item = &'a str>) -> Result<usize, String> {
+    match next_count(tokens)? {
+        0 => Err("Index 0 is not valid (indices are 1-based)".to_owned()),
+        index => Ok(index - 1),
+    }
+}
+
+fn next_float<'a>(tokens: &mut impl Iterator<Item = &'a str>) -> Result<Float, String> {
+    let token = tokens.next().ok_or("Unexpected end of file")?;
+    token.parse().map_err(|_| format!("Expected a number, found: {token}"))
+}
@@ -11,7 +11,7 @@ impl<M: Tag> Mesh<M>
 where
     M: std::default::Default + std::cmp::Eq + std::hash::Hash + Copy + Clone,
 {
-    pub fn from_obj(path: &PathBuf) -> Result<(Self, ids::IdMap<VERT, M>, ids::IdMap<FACE, M>), MeshError<M>> {
+    pub fn from_obj(path: &PathBuf) -> Result<MeshWithIds<M>, MeshError<M>> {
         match OpenOptions::new().read(true).open(path) {
             Ok(file) => match path.extension().unwrap().to_str() {
                 Some("obj") => match Self::obj_to_elements(BufReader::new(file)) {
@@ -26,7 +26,16 @@ where
         }
     }
 
+    // Write the mesh as an OBJ file, with vertex normals (default weighting).
     pub fn to_obj(&self, path: &PathBuf) -> Result<(), std::io::Error> {
+        let mut attributes = Attributes::default();
+        self.cache_vertex_normals(NormalWeighting::default(), &mut attributes);
+        self.to_obj_with_attributes(path, &attributes)
+    }
+
+    // Write the mesh as an OBJ file, with the vertex normals cached in the attributes (see `Mesh::cache_vertex_normals`), if any.
+    pub fn to_obj_with_attributes(&self, path: &PathBuf, attributes: &Attributes<M>) -> Result<(), std::io::Error> {
+        let normals = attributes.vertex_normals();
         let mut file = std::fs::File::create(path)?;
 
         let mut vert_ids = ids::IdMap::<VERT, M>::new();
@@ -48,18 +57,18 @@ where
                 .join("\n")
         )?;
 
+        // Normals are written in the same order as the vertices, such that a vertex and its normal share their index.
         writeln!(
             file,
             "{}",
-            self.face_ids()
+            self.vert_ids()
                 .into_iter()
-                .map(|face_id| {
-                    format!(
-                        "vn {x:.6} {y:.6} {z:.6}",
-                        x = self.normal(face_id).x,
-                        y = self.normal(face_id).y,
-                        z = self.normal(face_id).z
-                    )
+                .map(|vert_id| {
+                    let normal = normals
+                        .and_then(|normals| normals.get(vert_id))
+                        .copied()
+                        .unwrap_or_else(|| self.normal(vert_id));
+                    format!("vn {x:.6} {y:.6} {z:.6}", x = normal.x, y = normal.y, z = normal.z)
                 })
                 .join("\n")
         )?;
@@ -74,7 +83,7 @@ where
                         "f {}",
                         self.vertices(face_id)
                             .iter()
-                            .map(|vert_id| format!("{}", vert_ids.id(vert_id).unwrap()))
+                            .map(|vert_id| format!("{i}//{i}", i = vert_ids.id(vert_id).unwrap()))
                             .join(" ")
                     )
                 })
@@ -10,7 +10,7 @@ impl<M: Tag> Mesh<M>
 where
     M: std::default::Default + std::cmp::Eq + std::hash::Hash + Copy + Clone,
 {
-    pub fn from_stl(path: &PathBuf) -> Result<(Self, ids::IdMap<VERT, M>, ids::IdMap<FACE, M>), MeshError<M>> {
+    pub fn from_stl(path: &PathBuf) -> Result<MeshWithIds<M>, MeshError<M>> {
         match OpenOptions::new().read(true).open(path) {
             Ok(file) => match path.extension().unwrap().to_str() {
                 Some("stl") => match Self::stl_to_elements(BufReader::new(file)) {
@@ -0,0 +1,265 @@
+use crate::prelude::*;
+use itertools::Itertools;
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::PathBuf;
+
+// How the (3D) faces are laid out in the plane.
+#[derive(Clone, Copy, Debug)]
+pub enum SvgLayout<'a, M: Tag> {
+    // Orthographic projection onto the plane with the given normal (viewed from the side it points to).
+    Plane(Vector3D),
+    // Vertex coordinates of a parameterized mesh. Faces with a missing coordinate are skipped.
+    Uv(&'a ids::SecMap<VERT, M, Vector2D>),
+    // Corner coordinates per face, for example from `Mesh::project` or an unfolding. Faces are drawn as separate pieces.
+    Pieces(&'a ids::SecMap<FACE, M, Vec<Vector2D>>),
+}
+
+// Drawing of (a subset of) the faces of a mesh, with styling.
+// Colors are RGB in [0, 1], widths are in output units (pixels, or millimeters if a scale is set).
+#[derive(Clone, Debug)]
+pub struct Svg<'a, M: Tag> {
+    layout: SvgLayout<'a, M>,
+    faces: Option<Vec<FaceKey<M>>>,
+    fills: Option<&'a ids::SecMap<FACE, M, Color>>,
+    stroke: (Color, Float),
+    paths: Vec<(Vec<EdgeKey<M>>, Color, Float)>,
+    face_labels: Option<&'a ids::SecMap<FACE, M, String>>,
+    vert_labels: Option<&'a ids::SecMap<VERT, M, String>>,
+    scale: Option<Float>,
+}
+
+impl<'a, M: Tag> Svg<'a, M> {
+    #[must_use]
+    pub const fn new(layout: SvgLayout<'a, M>) -> Self {
+        Self {
+            layout,
+            faces: None,
+            fills: None,
+            stroke: ([0., 0., 0.], 1.),
+            paths: vec![],
+            face_labels: None,
+            vert_labels: None,
+            scale: None,
+        }
+    }
+
+    // Only draw the given faces (by default, all faces are drawn).
+    #[must_use]
+    pub fn with_faces(mut self, faces: Vec<FaceKey<M>>) -> Self {
+        self.faces = Some(faces);
+        self
+    }
+
+    // Fill color per face. Faces without a color are not filled.
+    #[must_use]
+    pub const fn with_fills(mut self, fills: &'a ids::SecMap<FACE, M, Color>) -> Self {
+        self.fills = Some(fills);
+        self
+    }
+
+    // Color and width of the edges. A width of zero hides the edges.
+    #[must_use]
+    pub const fn with_stroke(mut self, color: Color, width: Float) -> Self {
+        self.stroke = (color, width);
+        self
+    }
+
+    // Highlight a path of edges (drawn on top of the faces and edges).
+    #[must_use]
+    pub fn with_path(mut self, edges: Vec<EdgeKey<M>>, color: Color, width: Float) -> Self {
+        self.paths.push((edges, color, width));
+        self
+    }
+
+    // Text at the centroid of each face.
+    #[must_use]
+    pub const fn with_face_labels(mut self, labels: &'a ids::SecMap<FACE, M, String>) -> Self {
+        self.face_labels = Some(labels);
+        self
+    }
+
+    // Text at the position of each vertex.
+    #[must_use]
+    pub const fn with_vert_labels(mut self, labels: &'a ids::SecMap<VERT, M, String>) -> Self {
+        self.vert_labels = Some(labels);
+        self
+    }
+
+    // Write the drawing at true size: one layout unit becomes `scale` millimeters (useful for cutting layouts).
+    // By default, the drawing is fitted in 800 pixels.
+    #[must_use]
+    pub const fn with_scale(mut self, scale: Float) -> Self {
+        self.scale = Some(scale);
+        self
+    }
+}
+
+impl<M: Tag> Mesh<M>
+where
+    M: std::default::Default + std::cmp::Eq + std::hash::Hash + Copy + Clone,
+{
+    // Write a 2D drawing of the mesh as an SVG file.
+    pub fn to_svg(&self, path: &PathBuf, svg: &Svg<M>) -> Result<(), std::io::Error> {
+        let face_ids = svg.faces.clone().unwrap_or_else(|| self.face_ids());
+
+        // The 2D corners of every face that can be drawn. The y-axis is flipped, as it points down in SVG.
+        let polygons = face_ids
+            .iter()
+            .filter_map(|&face_id| Some((face_id, self.svg_corners(face_id, svg.layout)?)))
+            .map(|(face_id, corners)| (face_id, corners.into_iter().map(|c| Vector2D::new(c.x, -c.y)).collect_vec()))
+            .collect_vec();
+
+        let (min, max) = polygons
+            .iter()
+            .flat_map(|(_, corners)| corners)
+            .fold((Vector2D::new(INF, INF), Vector2D::new(NEG_INF, NEG_INF)), |(min, max), c| {
+                (min.inf(c), max.sup(c))
+            });
+        let (min, max) = if min.x > max.x { (Vector2D::zeros(), Vector2D::zeros()) } else { (min, max) };
+        let extent = (max - min).max().max(EPS);
+
+        // Sizes (stroke widths and fonts) are given in output units, and are converted to layout units.
+        let (width, height, unit, per_unit) = match svg.scale {
+            Some(scale) => ((max.x - min.x) * scale, (max.y - min.y) * scale, "mm", 1. / scale),
+            None => {
+                let fit = 800. / extent;
+                ((max.x - min.x) * fit, (max.y - min.y) * fit, "", 1. / fit)
+            }
+        };
+        let margin = extent * 0.02;
+        let font = 12. * per_unit;
+
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writeln!(file, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            file,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}{unit}" height="{}{unit}" viewBox="{} {} {} {}">"#,
+            width + 2. * margin / per_unit,
+            height + 2. * margin / per_unit,
+            min.x - margin,
+            min.y - margin,
+            max.x - min.x + 2. * margin,
+            max.y - min.y + 2. * margin,
+        )?;
+
+        if let Some(fills) = svg.fills {
+            writeln!(file, r#"  <g stroke="none">"#)?;
+            for (face_id, corners) in &polygons {
+                if let Some(&color) = fills.get(*face_id) {
+                    writeln!(file, r#"    <polygon points="{}" fill="{}"/>"#, points(corners), rgb(color))?;
+                }
+            }
+            writeln!(file, "  </g>")?;
+        }
+
+        let (color, width) = svg.stroke;
+        if width > 0. {
+            writeln!(
+                file,
+                r#"  <g fill="none" stroke="{}" stroke-width="{}" stroke-linejoin="round" stroke-linecap="round">"#,
+                rgb(color),
+                width * per_unit
+            )?;
+            if matches!(svg.layout, SvgLayout::Pieces(_)) {
+                for (_, corners) in &polygons {
+                    writeln!(file, r#"    <polygon points="{}"/>"#, points(corners))?;
+                }
+            } else {
+                // Shared edges are drawn once.
+                let mut drawn = HashSet::new();
+                for (face_id, corners) in &polygons {
+                    for (i, edge_id) in self.edges(*face_id).into_iter().enumerate() {
+                        if drawn.insert(edge_id) && !drawn.contains(&self.twin(edge_id)) {
+                            let (a, b) = (corners[i], corners[(i + 1) % corners.len()]);
+                            writeln!(file, r#"    <line x1="{}" y1="{}" x2="{}" y2="{}"/>"#, a.x, a.y, b.x, b.y)?;
+                        }
+                    }
+                }
+            }
+            writeln!(file, "  </g>")?;
+        }
+
+        for (edges, color, width) in &svg.paths {
+            let segments = edges
+                .iter()
+                .filter_map(|&edge_id| {
+                    let (_, corners) = polygons.iter().find(|(face_id, _)| *face_id == self.face(edge_id))?;
+                    let i = self.edges(self.face(edge_id)).iter().position(|&e| e == edge_id)?;
+                    let (a, b) = (corners[i], corners[(i + 1) % corners.len()]);
+                    Some(format!("M {} {} L {} {}", a.x, a.y, b.x, b.y))
+                })
+                .join(" ");
+            writeln!(
+                file,
+                r#"  <path d="{segments}" fill="none" stroke="{}" stroke-width="{}" stroke-linejoin="round" stroke-linecap="round"/>"#,
+                rgb(*color),
+                width * per_unit
+            )?;
+        }
+
+        if svg.face_labels.is_some() || svg.vert_labels.is_some() {
+            writeln!(
+                file,
+                r#"  <g font-family="sans-serif" font-size="{font}" text-anchor="middle" dominant-baseline="central">"#
+            )?;
+            if let Some(labels) = svg.face_labels {
+                for (face_id, corners) in &polygons {
+                    if let Some(label) = labels.get(*face_id) {
+                        let c = corners.iter().sum::<Vector2D>() / corners.len() as Float;
+                        writeln!(file, r#"    <text x="{}" y="{}">{}</text>"#, c.x, c.y, escape(label))?;
+                    }
+                }
+            }
+            if let Some(labels) = svg.vert_labels {
+                let mut drawn = HashSet::new();
+                for (face_id, corners) in &polygons {
+                    for (vert_id, c) in self.vertices(*face_id).into_iter().zip(corners) {
+                        let shared = !matches!(svg.layout, SvgLayout::Pieces(_));
+                        if let Some(label) = labels.get(vert_id)
+                            && (!shared || drawn.insert(vert_id))
+                        {
+                            writeln!(file, r#"    <text x="{}" y="{}">{}</text>"#, c.x, c.y, escape(label))?;
+                        }
+                    }
+                }
+            }
+            writeln!(file, "  </g>")?;
+        }
+
+        writeln!(file, "</svg>")?;
+        file.flush()
+    }
+
+    fn svg_corners(&self, face_id: FaceKey<M>, layout: SvgLayout<M>) -> Option<Vec<Vector2D>> {
+        match layout {
+            SvgLayout::Plane(normal) => {
+                let normal = normal.try_normalize(EPS)?;
+                let helper = if normal.x.abs() < 0.9 { Vector3D::x() } else { Vector3D::y() };
+                let u = (helper - normal * helper.dot(&normal)).normalize();
+                let v = normal.cross(&u);
+                Some(
+                    self.vertices(face_id)
+                        .into_iter()
+                        .map(|vert_id| geom::project_point_onto_plane(self.position(vert_id), (u, v), Vector3D::zeros()))
+                        .collect(),
+                )
+            }
+            SvgLayout::Uv(uvs) => self.vertices(face_id).into_iter().map(|vert_id| uvs.get(vert_id).copied()).collect(),
+            SvgLayout::Pieces(pieces) => pieces.get(face_id).filter(|corners| corners.len() == self.vertices(face_id).len()).cloned(),
+        }
+    }
+}
+
+fn points(corners: &[Vector2D]) -> String {
+    corners.iter().map(|c| format!("{},{}", c.x, c.y)).join(" ")
+}
+
+fn rgb(color: Color) -> String {
+    let [r, g, b] = color.map(|c| (c.clamp(0., 1.) * 255.).round() as u8);
+    format!("rgb({r},{g},{b})")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
@@ -0,0 +1,243 @@
+use crate::prelude::*;
+use itertools::Itertools;
+use std::io::Write;
+use std::path::PathBuf;
+
+// A named data array, attached to the points (vertices) or cells (faces) of an exported mesh.
+// Elements without a value in the map are written as zero.
+#[derive(Clone, Copy, Debug)]
+pub enum VtkArray<'a, K: Eq + std::hash::Hash, M: Tag> {
+    Scalars(&'a ids::SecMap<K, M, Float>),
+    Vectors(&'a ids::SecMap<K, M, Vector3D>),
+}
+
+impl<K: Eq + std::hash::Hash, M: Tag> VtkArray<'_, K, M> {
+    const fn components(&self) -> usize {
+        match self {
+            Self::Scalars(_) => 1,
+            Self::Vectors(_) => 3,
+        }
+    }
+
+    fn values(&self, id: ids::Key<K, M>) -> Vec<Float> {
+        match self {
+            Self::Scalars(map) => vec![map.get(id).copied().unwrap_or_default()],
+            Self::Vectors(map) => map.get(id).copied().unwrap_or_default().as_slice().to_vec(),
+        }
+    }
+}
+
+// Collection of point and cell data arrays to write alongside the mesh.
+// Attributes of the mesh itself can be added by first collecting them with `Mesh::vert_attribute` or `Mesh::face_attribute`.
+#[derive(Clone, Debug)]
+pub struct VtkData<'a, M: Tag> {
+    point_data: Vec<(String, VtkArray<'a, VERT, M>)>,
+    cell_data: Vec<(String, VtkArray<'a, FACE, M>)>,
+}
+
+impl<M: Tag> Default for VtkData<'_, M> {
+    fn default() -> Self {
+        Self {
+            point_data: vec![],
+            cell_data: vec![],
+        }
+    }
+}
+
+impl<'a, M: Tag> VtkData<'a, M> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn with_point_scalars(mut self, name: &str, map: &'a ids::SecMap<VERT, M, Float>) -> Self {
+        self.point_data.push((sanitize(name), VtkArray::Scalars(map)));
+        self
+    }
+
+    #[must_use]
+    pub fn with_point_vectors(mut self, name: &str, map: &'a ids::SecMap<VERT, M, Vector3D>) -> Self {
+        self.point_data.push((sanitize(name), VtkArray::Vectors(map)));
+        self
+    }
+
+    #[must_use]
+    pub fn with_cell_scalars(mut self, name: &str, map: &'a ids::SecMap<FACE, M, Float>) -> Self {
+        self.cell_data.push((sanitize(name), VtkArray::Scalars(map)));
+        self
+    }
+
+    #[must_use]
+    pub fn with_cell_vectors(mut self, name: &str, map: &'a ids::SecMap<FACE, M, Vector3D>) -> Self {
+        self.cell_data.push((sanitize(name), VtkArray::Vectors(map)));
+        self
+    }
+}
+
+impl<M: Tag> Mesh<M>
+where
+    M: std::default::Default + std::cmp::Eq + std::hash::Hash + Copy + Clone,
+{
+    // Write the mesh as a legacy (ASCII) VTK polydata file.
+    pub fn to_vtk(&self, path: &PathBuf, data: &VtkData<M>) -> Result<(), std::io::Error> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        let (vert_ids, face_ids) = (self.vert_ids(), self.face_ids());
+        let vert_map = self.vert_index_map();
+
+        writeln!(file, "# vtk DataFile Version 3.0")?;
+        writeln!(file, "mehsh")?;
+        writeln!(file, "ASCII")?;
+        writeln!(file, "DATASET POLYDATA")?;
+
+        writeln!(file, "POINTS {} double", vert_ids.len())?;
+        for &vert_id in &vert_ids {
+            let p = self.position(vert_id);
+            writeln!(file, "{} {} {}", p.x, p.y, p.z)?;
+        }
+
+        let size = face_ids.iter().map(|&face_id| self.vertices(face_id).len() + 1).sum::<usize>();
+        writeln!(file, "POLYGONS {} {size}", face_ids.len())?;
+        for &face_id in &face_ids {
+            let corners = self.vertices(face_id);
+            writeln!(
+                file,
+                "{} {}",
+                corners.len(),
+                corners.iter().map(|vert_id| vert_map.id(vert_id).unwrap()).join(" ")
+            )?;
+        }
+
+        if !data.point_data.is_empty() {
+            writeln!(file, "POINT_DATA {}", vert_ids.len())?;
+            for (name, array) in &data.point_data {
+                write_legacy_array(&mut file, name, array, &vert_ids)?;
+            }
+        }
+
+        if !data.cell_data.is_empty() {
+            writeln!(file, "CELL_DATA {}", face_ids.len())?;
+            for (name, array) in &data.cell_data {
+                write_legacy_array(&mut file, name, array, &face_ids)?;
+            }
+        }
+
+        file.flush()
+    }
+
+    // Write the mesh as an XML (ASCII) VTK unstructured grid file.
+    pub fn to_vtu(&self, path: &PathBuf, data: &VtkData<M>) -> Result<(), std::io::Error> {
+        self.to_vtk_xml(path, data, "UnstructuredGrid")
+    }
+
+    // Write the mesh as an XML (ASCII) VTK polydata file.
+    pub fn to_vtp(&self, path: &PathBuf, data: &VtkData<M>) -> Result<(), std::io::Error> {
+        self.to_vtk_xml(path, data, "PolyData")
+    }
+
+    fn to_vtk_xml(&self, path: &PathBuf, data: &VtkData<M>, kind: &str) -> Result<(), std::io::Error> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        let (vert_ids, face_ids) = (self.vert_ids(), self.face_ids());
+        let vert_map = self.vert_index_map();
+
+        let corners = face_ids.iter().map(|&face_id| self.vertices(face_id)).collect_vec();
+        let connectivity = corners.iter().flatten().map(|vert_id| vert_map.id(vert_id).unwrap()).join(" ");
+        let offsets = corners
+            .iter()
+            .scan(0, |offset, corners| {
+                *offset += corners.len();
+                Some(*offset)
+            })
+            .join(" ");
+
+        writeln!(file, r#"<?xml version="1.0"?>"#)?;
+        writeln!(file, r#"<VTKFile type="{kind}" version="1.0" byte_order="LittleEndian">"#)?;
+        writeln!(file, "  <{kind}>")?;
+        if kind == "PolyData" {
+            writeln!(file, r#"    <Piece NumberOfPoints="{}" NumberOfPolys="{}">"#, vert_ids.len(), face_ids.len())?;
+        } else {
+            writeln!(file, r#"    <Piece NumberOfPoints="{}" NumberOfCells="{}">"#, vert_ids.len(), face_ids.len())?;
+        }
+
+        writeln!(file, "      <Points>")?;
+        let points = vert_ids
+            .iter()
+            .map(|&vert_id| self.position(vert_id))
+            .map(|p| format!("{} {} {}", p.x, p.y, p.z))
+            .join(" ");
+        write_xml_array(&mut file, "Float64", None, 3, &points)?;
+        writeln!(file, "      </Points>")?;
+
+        let cells = if kind == "PolyData" { "Polys" } else { "Cells" };
+        writeln!(file, "      <{cells}>")?;
+        write_xml_array(&mut file, "Int64", Some("connectivity"), 1, &connectivity)?;
+        write_xml_array(&mut file, "Int64", Some("offsets"), 1, &offsets)?;
+        if kind != "PolyData" {
+            // VTK cell types: 5 = triangle, 9 = quad, 7 = polygon.
+            let types = corners
+                .iter()
+                .map(|corners| match corners.len() {
+                    3 => 5,
+                    4 => 9,
+                    _ => 7,
+                })
+                .join(" ");
+            write_xml_array(&mut file, "UInt8", Some("types"), 1, &types)?;
+        }
+        writeln!(file, "      </{cells}>")?;
+
+        writeln!(file, "      <PointData>")?;
+        for (name, array) in &data.point_data {
+            let values = vert_ids.iter().flat_map(|&vert_id| array.values(vert_id)).join(" ");
+            write_xml_array(&mut file, "Float64", Some(name), array.components(), &values)?;
+        }
+        writeln!(file, "      </PointData>")?;
+
+        writeln!(file, "      <CellData>")?;
+        for (name, array) in &data.cell_data {
+            let values = face_ids.iter().flat_map(|&face_id| array.values(face_id)).join(" ");
+            write_xml_array(&mut file, "Float64", Some(name), array.components(), &values)?;
+        }
+        writeln!(file, "      </CellData>")?;
+
+        writeln!(file, "    </Piece>")?;
+        writeln!(file, "  </{kind}>")?;
+        writeln!(file, "</VTKFile>")?;
+
+        file.flush()
+    }
+}
+
+fn write_legacy_array<K: Copy + Eq + std::hash::Hash, M: Tag>(
+    file: &mut impl Write,
+    name: &str,
+    array: &VtkArray<K, M>,
+    ids: &[ids::Key<K, M>],
+) -> Result<(), std::io::Error> {
+    match array {
+        VtkArray::Scalars(_) => {
+            writeln!(file, "SCALARS {name} double 1")?;
+            writeln!(file, "LOOKUP_TABLE default")?;
+        }
+        VtkArray::Vectors(_) => writeln!(file, "VECTORS {name} double")?,
+    }
+    for &id in ids {
+        writeln!(file, "{}", array.values(id).iter().join(" "))?;
+    }
+    Ok(())
+}
+
+fn write_xml_array(file: &mut impl Write, kind: &str, name: Option<&str>, components: usize, values: &str) -> Result<(), std::io::Error> {
+    let name = name.map(|name| format!(r#" Name="{name}""#)).unwrap_or_default();
+    writeln!(
+        file,
+        r#"        <DataArray type="{kind}"{name} NumberOfComponents="{components}" format="ascii">"#
+    )?;
+    writeln!(file, "          {values}")?;
+    writeln!(file, "        </DataArray>")
+}
+
+// Array names cannot contain whitespace in legacy VTK files, and should not contain markup in XML files.
+fn sanitize(name: &str) -> String {
+    name.chars().map(|c| if c.is_whitespace() || "<>&\"'".contains(c) { '_' } else { c }).collect()
+}
@@ -1,6 +1,9 @@
 pub mod mesh {
     pub mod builder;
     pub mod connectivity;
+    pub mod labels;
+    pub mod native;
+    pub mod shapes;
     pub mod verify;
     pub mod elem {
         pub mod edge;
@@ -8,8 +11,19 @@ pub mod mesh {
         pub mod vert;
     }
     pub mod algo {
+        pub mod boolean;
+        pub mod bounds;
+        pub mod curvature;
+        pub mod deviation;
+        pub mod hull;
+        pub mod integrals;
+        pub mod intersect;
+        pub mod isosurface;
+        pub mod operators;
         pub mod projection;
         pub mod refine;
+        pub mod sampling;
+        pub mod slice;
         pub mod triangulate;
         pub mod location {
             pub mod face;
@@ -21,32 +35,69 @@ pub mod mesh {
 pub mod integrations {
     #[cfg(feature = "bevy")]
     pub mod bevy;
+    #[cfg(feature = "gltf")]
+    pub mod gltf;
+    #[cfg(feature = "gmsh")]
+    pub mod gmsh;
+    #[cfg(feature = "medit")]
+    pub mod medit;
     #[cfg(feature = "obj")]
     pub mod obj;
     #[cfg(feature = "petgraph")]
     pub mod petgraph;
     #[cfg(feature = "stl")]
     pub mod stl;
+    #[cfg(feature = "svg")]
+    pub mod svg;
+    #[cfg(feature = "vtk")]
+    pub mod vtk;
 }
 
 pub mod utils {
     pub mod geom;
+    pub mod grid;
     pub mod ids;
     pub mod math;
     pub mod primitives;
+    pub mod sparse;
 }
 
 pub mod prelude {
     pub use crate::define_tag;
-    pub use crate::mesh::algo::location::{face::FaceLocation, vert::VertLocation};
+    #[cfg(feature = "gltf")]
+    pub use crate::integrations::gltf::GltfColors;
+    #[cfg(feature = "svg")]
+    pub use crate::integrations::svg::{Svg, SvgLayout};
+    #[cfg(feature = "vtk")]
+    pub use crate::integrations::vtk::{VtkArray, VtkData};
+    pub use crate::mesh::algo::boolean::{BooleanOperation, FaceOrigin, FaceOrigins};
+    pub use crate::mesh::algo::bounds::{BoundingSphere, BoxFit, OrientedBox};
+    pub use crate::mesh::algo::curvature::PrincipalCurvature;
+    pub use crate::mesh::algo::deviation::Deviation;
+    pub use crate::mesh::algo::hull::VertOrigins;
+    pub use crate::mesh::algo::isosurface::Isosurface;
+    pub use crate::mesh::algo::location::{
+        face::{FaceLocation, Hit, SurfacePoint},
+        vert::VertLocation,
+    };
+    pub use crate::mesh::algo::operators::MassMatrix;
+    pub use crate::mesh::algo::sampling::{Metric, Sample};
+    pub use crate::mesh::algo::slice::ContourPoint;
+    pub use crate::mesh::algo::triangulate::FaceParents;
     pub use crate::mesh::connectivity::{
-        EDGE, EdgeKey, FACE, FaceKey, HasEdges, HasFaces, HasNeighbors, HasNormal, HasPosition, HasSize, HasVertices, Mesh, MeshError, SetPosition, Tag, VERT,
-        VertKey,
+        EDGE, EdgeKey, FACE, FaceKey, HasEdges, HasFaces, HasNeighbors, HasNormal, HasPosition, HasSize, HasVertices, Mesh, MeshError, MeshWithIds,
+        SetPosition, Tag, VERT, VertKey,
     };
+    pub use crate::mesh::elem::vert::{NormalWeighting, VERTEX_NORMALS};
+    pub use crate::mesh::labels::{LabeledMeshWithIds, Labels};
+    pub use crate::mesh::native::{Attribute, Attributes, NativeFile, NativeOptions};
+    pub use crate::mesh::shapes::Platonic;
     pub use crate::utils::geom;
+    pub use crate::utils::grid::Grid;
     pub use crate::utils::ids;
     pub use crate::utils::math;
     pub use crate::utils::primitives::*;
+    pub use crate::utils::sparse;
 }
 
 #[cfg(test)]
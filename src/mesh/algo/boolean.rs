@@ -0,0 +1,492 @@
+use crate::mesh::algo::intersect::Welder;
+use crate::prelude::*;
+use geom::TriangleIntersection;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BooleanOperation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+// Face of the input meshes that a face of the result of a boolean operation came from (the first or the second operand).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FaceOrigin<M: Tag> {
+    First(FaceKey<M>),
+    Second(FaceKey<M>),
+}
+
+// Origin of every face of the result of a boolean operation (see `FaceOrigin`).
+pub type FaceOrigins<M> = ids::SecMap<FACE, M, FaceOrigin<M>>;
+
+// Where a triangle of one operand lies with respect to the other operand. On its surface, it either has the same or the opposite orientation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Side {
+    Outside,
+    Inside,
+    Same,
+    Opposite,
+}
+
+// Boolean operations (constructive solid geometry) between the solids enclosed by two closed meshes.
+//      1. The faces are triangulated, and the triangles of both meshes are intersected (with exact orientation predicates, see `geom::triangle_triangle_intersection`).
+//         Constructed intersection points closer than a small tolerance (relative to the coordinates) are merged.
+//      2. Triangles are split along their intersection segments (and, where they overlap coplanar triangles, along the edges of those triangles).
+//      3. Every region of pieces (connected without crossing an intersection) is kept or discarded based on whether it lies inside or outside the other mesh (generalized winding number),
+//         or on its surface. Of overlapping coplanar pieces, only the piece of the first mesh is kept.
+// Faces that are not split are kept as they are, other faces become triangles.
+// The result comes with the face of the input meshes that each face came from. It is an error if the result is not a closed 2-manifold.
+impl<M: Tag> Mesh<M> {
+    pub fn union(&self, other: &Self) -> Result<(Self, FaceOrigins<M>), MeshError<M>> {
+        self.boolean(other, BooleanOperation::Union)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Result<(Self, FaceOrigins<M>), MeshError<M>> {
+        self.boolean(other, BooleanOperation::Intersection)
+    }
+
+    pub fn difference(&self, other: &Self) -> Result<(Self, FaceOrigins<M>), MeshError<M>> {
+        self.boolean(other, BooleanOperation::Difference)
+    }
+
+    pub fn boolean(&self, other: &Self, operation: BooleanOperation) -> Result<(Self, FaceOrigins<M>), MeshError<M>> {
+        let meshes = [self, other];
+        let locations = [self.bvh(), other.bvh()];
+        let positions = meshes
+            .iter()
+            .flat_map(|mesh| mesh.vert_ids().into_iter().map(|vert_id| mesh.position(vert_id)))
+            .collect_vec();
+        let tolerance = 1e-9 * geom::scale(&positions);
+
+        // Vertices of both meshes (which are merged if they coincide), followed by the intersection points.
+        let mut welder = Welder::new(tolerance);
+        let points = meshes.map(|mesh| {
+            mesh.vert_ids()
+                .into_iter()
+                .map(|vert_id| (vert_id, welder.insert(mesh.position(vert_id))))
+                .collect::<HashMap<_, _>>()
+        });
+        let mut pieces = [0, 1].map(|k| {
+            (0..locations[k].nr_triangles())
+                .map(|i| {
+                    let (_, face_corners, face_id) = locations[k].triangle(i);
+                    let verts = meshes[k].vertices(face_id);
+                    Piece {
+                        face: face_id,
+                        corners: face_corners.map(|corner| points[k][&verts[corner]]),
+                        points: vec![],
+                        constraints: vec![],
+                    }
+                })
+                .collect_vec()
+        });
+
+        // 1. Intersect the triangles, and collect the segments along which they have to be split.
+        for i in 0..locations[0].nr_triangles() {
+            let (t, _, _) = locations[0].triangle(i);
+            for j in locations[1].overlapping(t) {
+                let (u, _, _) = locations[1].triangle(j);
+                match geom::triangle_triangle_intersection((t[0], t[1], t[2]), (u[0], u[1], u[2])) {
+                    Some(TriangleIntersection::Segment(a, b)) => {
+                        let segment = (welder.insert(a), welder.insert(b));
+                        pieces[0][i].constraints.push(segment);
+                        pieces[1][j].constraints.push(segment);
+                    }
+                    Some(TriangleIntersection::Coplanar) => {
+                        for (from, to, k, index) in [(u, t, 0, i), (t, u, 1, j)] {
+                            for edge in 0..3 {
+                                if let Some((a, b)) = clip_segment(from[edge], from[(edge + 1) % 3], to) {
+                                    let segment = (welder.insert(a), welder.insert(b));
+                                    pieces[k][index].constraints.push(segment);
+                                }
+                            }
+                        }
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        // Split points on an edge are shared by both triangles next to it (of either mesh), such that their splits match.
+        let mut edge_points = HashMap::<(usize, usize), Vec<usize>>::new();
+        for piece in pieces.iter_mut().flatten() {
+            piece.constraints.retain(|&(a, b)| a != b);
+            for p in piece.constraints.iter().flat_map(|&(a, b)| [a, b]).unique().collect_vec() {
+                if piece.corners.contains(&p) {
+                    continue;
+                }
+                let on_edge = (0..3).find(|&edge| {
+                    let (a, b) = (welder.points[piece.corners[edge]], welder.points[piece.corners[(edge + 1) % 3]]);
+                    (welder.points[p] - a).cross(&(b - a)).norm() <= tolerance * (b - a).norm()
+                });
+                match on_edge {
+                    Some(edge) => {
+                        let (a, b) = (piece.corners[edge], piece.corners[(edge + 1) % 3]);
+                        edge_points.entry((a.min(b), a.max(b))).or_default().push(p);
+                    }
+                    None => piece.points.push(p),
+                }
+            }
+        }
+
+        let keep = |k: usize, side: Side| match operation {
+            BooleanOperation::Union => matches!((k, side), (0, Side::Outside | Side::Same) | (1, Side::Outside)),
+            BooleanOperation::Intersection => matches!((k, side), (0, Side::Inside | Side::Same) | (1, Side::Inside)),
+            BooleanOperation::Difference => matches!((k, side), (0, Side::Outside | Side::Opposite) | (1, Side::Inside)),
+        };
+        let mut faces = vec![];
+        let mut origins = vec![];
+        for k in 0..2 {
+            // 2. Split the triangles.
+            let mut triangles = vec![];
+            let mut face_triangles = HashMap::<FaceKey<M>, Vec<usize>>::new();
+            let mut intact = HashMap::<FaceKey<M>, bool>::new();
+            let mut constrained = HashSet::new();
+            for piece in &pieces[k] {
+                let edge_points = (0..3)
+                    .map(|edge| {
+                        let (a, b) = (piece.corners[edge], piece.corners[(edge + 1) % 3]);
+                        let parameter = |p: usize| (welder.points[p] - welder.points[a]).dot(&(welder.points[b] - welder.points[a]));
+                        edge_points
+                            .get(&(a.min(b), a.max(b)))
+                            .into_iter()
+                            .flatten()
+                            .unique()
+                            .copied()
+                            .sorted_by(|&p, &q| parameter(p).total_cmp(&parameter(q)))
+                            .collect_vec()
+                    })
+                    .collect_vec();
+                let unchanged = piece.points.is_empty() && edge_points.iter().all(Vec::is_empty);
+                *intact.entry(piece.face).or_insert(true) &= unchanged;
+                let (split, edges) = if unchanged {
+                    (vec![piece.corners], vec![])
+                } else {
+                    piece
+                        .split(&welder.points, &edge_points)
+                        .ok_or_else(|| MeshError::Unknown(format!("could not split the triangles of {:?} along the intersections", piece.face)))?
+                };
+                constrained.extend(edges.into_iter().map(|(a, b)| (a.min(b), a.max(b))));
+                for triangle in split {
+                    face_triangles.entry(piece.face).or_default().push(triangles.len());
+                    triangles.push(triangle);
+                }
+            }
+
+            // 3. Classify the regions (triangles connected without crossing an intersection) by the triangle that is farthest away from the other mesh,
+            //    as pieces next to an intersection can be arbitrarily thin. Regions that lie on the surface of the other mesh are classified by their largest triangle.
+            let corners = |triangle: [usize; 3]| triangle.map(|p| welder.points[p]);
+            let centroid = |triangle: [usize; 3]| corners(triangle).iter().sum::<Vector3D>() / 3.;
+            let vector_area = |triangle: [usize; 3]| {
+                let [a, b, c] = corners(triangle);
+                (b - a).cross(&(c - a))
+            };
+            let regions = regions(&triangles, &constrained);
+            let sides = regions
+                .iter()
+                .map(|region| {
+                    let (distance, farthest) = region
+                        .iter()
+                        .map(|&t| {
+                            (
+                                locations[1 - k].closest_point(centroid(triangles[t])).map_or(INF, |closest| closest.distance),
+                                t,
+                            )
+                        })
+                        .max_by(|a, b| a.0.total_cmp(&b.0))
+                        .unwrap();
+                    if distance > tolerance {
+                        return if locations[1 - k].contains(centroid(triangles[farthest])) {
+                            Side::Inside
+                        } else {
+                            Side::Outside
+                        };
+                    }
+                    let largest = region
+                        .iter()
+                        .copied()
+                        .max_by(|&a, &b| vector_area(triangles[a]).norm().total_cmp(&vector_area(triangles[b]).norm()))
+                        .unwrap();
+                    let closest = locations[1 - k].closest_point(centroid(triangles[largest])).unwrap();
+                    if vector_area(triangles[largest]).dot(&meshes[1 - k].normal(closest.face)) > 0. {
+                        Side::Same
+                    } else {
+                        Side::Opposite
+                    }
+                })
+                .collect_vec();
+            let mut side = vec![Side::Outside; triangles.len()];
+            for (region, &region_side) in regions.iter().zip(&sides) {
+                for &t in region {
+                    side[t] = region_side;
+                }
+            }
+
+            let flip = operation == BooleanOperation::Difference && k == 1;
+            let origin = |face_id| if k == 0 { FaceOrigin::First(face_id) } else { FaceOrigin::Second(face_id) };
+            for face_id in meshes[k].face_ids() {
+                let mut kept = if intact[&face_id] {
+                    let t = face_triangles[&face_id][0];
+                    if keep(k, side[t]) {
+                        vec![meshes[k].vertices(face_id).into_iter().map(|vert_id| points[k][&vert_id]).collect_vec()]
+                    } else {
+                        vec![]
+                    }
+                } else {
+                    face_triangles[&face_id]
+                        .iter()
+                        .filter(|&&t| keep(k, side[t]))
+                        .map(|&t| triangles[t].to_vec())
+                        .collect_vec()
+                };
+                if flip {
+                    kept.iter_mut().for_each(|face| face.reverse());
+                }
+                origins.extend(vec![origin(face_id); kept.len()]);
+                faces.extend(kept);
+            }
+        }
+
+        // Only the points that are used by the result.
+        let used = faces.iter().flatten().copied().unique().sorted().collect_vec();
+        let index = used.iter().enumerate().map(|(i, &p)| (p, i)).collect::<HashMap<_, _>>();
+        let faces = faces.into_iter().map(|face| face.into_iter().map(|p| index[&p]).collect_vec()).collect_vec();
+        let positions = used.iter().map(|&p| welder.points[p]).collect_vec();
+
+        let (mesh, _, face_map) = Self::from(&faces, &positions)?;
+        let mut face_origins = ids::SecMap::new();
+        for (i, origin) in origins.into_iter().enumerate() {
+            face_origins.insert(*face_map.key(i).unwrap(), origin);
+        }
+        Ok((mesh, face_origins))
+    }
+}
+
+// A triangle of a face of one of the operands (with corners, points and constraints as indices of merged points),
+// with the points and segments (constraints) along which it has to be split.
+struct Piece<M: Tag> {
+    face: FaceKey<M>,
+    corners: [usize; 3],
+    points: Vec<usize>,
+    constraints: Vec<(usize, usize)>,
+}
+
+// Triangles (as indices of merged points), with the edges along the constraints.
+type ConstrainedTriangles = (Vec<[usize; 3]>, Vec<(usize, usize)>);
+
+impl<M: Tag> Piece<M> {
+    // Triangulation of the triangle with the given points on its edges (sorted from the start to the end of every edge), and the points and constraints in its interior.
+    // The triangles have the same orientation as the original triangle, and come with the edges along the constraints. None if a constraint could not be recovered.
+    fn split(&self, positions: &[Vector3D], edge_points: &[Vec<usize>]) -> Option<ConstrainedTriangles> {
+        let [a, b, c] = self.corners.map(|p| positions[p]);
+        let axis = geom::dominant_axis((b - a).cross(&(c - a)));
+        let mut triangulation = Triangulation {
+            positions: self
+                .corners
+                .iter()
+                .chain(edge_points.iter().flatten())
+                .chain(&self.points)
+                .map(|&p| (p, geom::drop_axis(positions[p], axis)))
+                .collect(),
+            triangles: vec![self.corners],
+            sign: 1.,
+            constrained: vec![],
+        };
+        triangulation.sign = triangulation.orient(self.corners[0], self.corners[1], self.corners[2]).signum();
+        if triangulation.sign == 0. {
+            return Some((vec![self.corners], vec![]));
+        }
+
+        for (edge, points) in edge_points.iter().enumerate() {
+            let mut start = self.corners[edge];
+            for &p in points {
+                triangulation.split_edge(start, self.corners[(edge + 1) % 3], p);
+                start = p;
+            }
+        }
+        for &p in &self.points {
+            triangulation.insert(p);
+        }
+        for &(p, q) in &self.constraints {
+            if !triangulation.recover(p, q) {
+                return None;
+            }
+        }
+        Some((triangulation.triangles, triangulation.constrained))
+    }
+}
+
+// Groups of triangles that are connected across edges that are not constrained.
+fn regions(triangles: &[[usize; 3]], constrained: &HashSet<(usize, usize)>) -> Vec<Vec<usize>> {
+    let mut edges = HashMap::<(usize, usize), Vec<usize>>::new();
+    for (t, triangle) in triangles.iter().enumerate() {
+        for i in 0..3 {
+            let (a, b) = (triangle[i], triangle[(i + 1) % 3]);
+            edges.entry((a.min(b), a.max(b))).or_default().push(t);
+        }
+    }
+    let mut visited = vec![false; triangles.len()];
+    let mut regions = vec![];
+    for start in 0..triangles.len() {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let (mut region, mut stack) = (vec![], vec![start]);
+        while let Some(t) = stack.pop() {
+            region.push(t);
+            for i in 0..3 {
+                let (a, b) = (triangles[t][i], triangles[t][(i + 1) % 3]);
+                if constrained.contains(&(a.min(b), a.max(b))) {
+                    continue;
+                }
+                for &neighbor in &edges[&(a.min(b), a.max(b))] {
+                    if !visited[neighbor] {
+                        visited[neighbor] = true;
+                        stack.push(neighbor);
+                    }
+                }
+            }
+        }
+        regions.push(region);
+    }
+    regions
+}
+
+// Triangulation (in 2D) of a single triangle, for splitting it.
+struct Triangulation {
+    positions: HashMap<usize, Vector2D>,
+    triangles: Vec<[usize; 3]>,
+    // Orientation of the triangles (the sign of `geom::orient2d`).
+    sign: f64,
+    // Edges along the recovered constraints.
+    constrained: Vec<(usize, usize)>,
+}
+
+impl Triangulation {
+    fn orient(&self, a: usize, b: usize, c: usize) -> f64 {
+        self.sign * geom::orient2d(self.positions[&a], self.positions[&b], self.positions[&c])
+    }
+
+    // The triangle with directed edge (a, b), as its index and its third corner.
+    fn triangle_with_edge(&self, a: usize, b: usize) -> Option<(usize, usize)> {
+        self.triangles
+            .iter()
+            .enumerate()
+            .find_map(|(index, t)| (0..3).find(|&i| t[i] == a && t[(i + 1) % 3] == b).map(|i| (index, t[(i + 2) % 3])))
+    }
+
+    // Split the edge between `a` and `b` (and the triangles on either side) at `p`.
+    fn split_edge(&mut self, a: usize, b: usize, p: usize) {
+        for (u, v) in [(a, b), (b, a)] {
+            if let Some((index, c)) = self.triangle_with_edge(u, v) {
+                self.triangles[index] = [u, p, c];
+                self.triangles.push([p, v, c]);
+            }
+        }
+    }
+
+    fn insert(&mut self, p: usize) {
+        // The triangle containing the point, or the one closest to containing it (the point may lie slightly outside due to rounding).
+        let Some((index, orientations)) = self
+            .triangles
+            .iter()
+            .enumerate()
+            .map(|(index, &[a, b, c])| (index, [self.orient(a, b, p), self.orient(b, c, p), self.orient(c, a, p)]))
+            .max_by(|(_, x), (_, y)| {
+                x.iter()
+                    .copied()
+                    .fold(f64::MAX, f64::min)
+                    .total_cmp(&y.iter().copied().fold(f64::MAX, f64::min))
+            })
+        else {
+            return;
+        };
+        let [a, b, c] = self.triangles[index];
+        match orientations.iter().filter(|&&o| o == 0.).count() {
+            0 => {
+                self.triangles[index] = [a, b, p];
+                self.triangles.push([b, c, p]);
+                self.triangles.push([c, a, p]);
+            }
+            1 => {
+                let edge = orientations.iter().position(|&o| o == 0.).unwrap();
+                let corners = [a, b, c];
+                self.split_edge(corners[edge], corners[(edge + 1) % 3], p);
+            }
+            // Coincides with a corner.
+            _ => {}
+        }
+    }
+
+    // Make the segment between `p` and `q` an edge of the triangulation (or a sequence of edges, if it passes through other points),
+    // by flipping the edges that cross it (Sloan, 1993). Returns false if that fails.
+    fn recover(&mut self, p: usize, q: usize) -> bool {
+        if p == q {
+            return true;
+        }
+        if self.triangle_with_edge(p, q).is_some() || self.triangle_with_edge(q, p).is_some() {
+            self.constrained.push((p, q));
+            return true;
+        }
+        let (pp, qq) = (self.positions[&p], self.positions[&q]);
+        let between = self.positions.keys().copied().find(|&r| {
+            r != p && r != q && self.orient(p, q, r) == 0. && (self.positions[&r] - pp).dot(&(qq - pp)) > 0. && (self.positions[&r] - qq).dot(&(pp - qq)) > 0.
+        });
+        if let Some(r) = between {
+            return self.recover(p, r) && self.recover(r, q);
+        }
+
+        let crosses = |s: &Self, a: usize, b: usize| {
+            ![p, q].contains(&a) && ![p, q].contains(&b) && s.orient(p, q, a) * s.orient(p, q, b) < 0. && s.orient(a, b, p) * s.orient(a, b, q) < 0.
+        };
+        for _ in 0..self.triangles.len().pow(2) + 1 {
+            if self.triangle_with_edge(p, q).is_some() || self.triangle_with_edge(q, p).is_some() {
+                self.constrained.push((p, q));
+                return true;
+            }
+            // A crossing edge whose neighboring triangles form a convex quadrilateral.
+            let flippable = self
+                .triangles
+                .iter()
+                .flat_map(|t| (0..3).map(move |i| (t[i], t[(i + 1) % 3])))
+                .find_map(|(a, b)| {
+                    if !crosses(self, a, b) {
+                        return None;
+                    }
+                    let ((first, c), (second, d)) = (self.triangle_with_edge(a, b)?, self.triangle_with_edge(b, a)?);
+                    (self.orient(a, d, c) > 0. && self.orient(d, b, c) > 0.).then_some((first, second, a, b, c, d))
+                });
+            let Some((first, second, a, b, c, d)) = flippable else {
+                return false;
+            };
+            self.triangles[first] = [a, d, c];
+            self.triangles[second] = [d, b, c];
+        }
+        false
+    }
+}
+
+// Part of the segment from `a` to `b` that lies in triangle `t` (in the same plane), if it has a positive length.
+fn clip_segment(a: Vector3D, b: Vector3D, t: [Vector3D; 3]) -> Option<(Vector3D, Vector3D)> {
+    let axis = geom::dominant_axis((t[1] - t[0]).cross(&(t[2] - t[0])));
+    let [ta, tb, tc] = t.map(|p| geom::drop_axis(p, axis));
+    let sign = geom::orient2d(ta, tb, tc).signum();
+    let (pa, pb) = (geom::drop_axis(a, axis), geom::drop_axis(b, axis));
+    let (mut start, mut end) = (0., 1.);
+    for (u, v) in [(ta, tb), (tb, tc), (tc, ta)] {
+        // Signed (inward) distances of the endpoints to the edge.
+        let (da, db) = (sign * geom::orient2d(u, v, pa), sign * geom::orient2d(u, v, pb));
+        if da < 0. && db < 0. {
+            return None;
+        }
+        if da < 0. {
+            start = Float::max(start, da / (da - db));
+        } else if db < 0. {
+            end = Float::min(end, da / (da - db));
+        }
+    }
+    (start < end).then(|| (a + (b - a) * start, a + (b - a) * end))
+}
@@ -0,0 +1,246 @@
+use crate::mesh::algo::hull::convex_polygon;
+use crate::prelude::*;
+use itertools::Itertools;
+use rand::{SeedableRng, rngs::StdRng, seq::SliceRandom};
+
+// Box with center `center`, axes the columns of `axes` (orthonormal, right-handed), and half of its size along each axis `half_extents`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OrientedBox {
+    pub center: Vector3D,
+    pub axes: nalgebra::Rotation3<Float>,
+    pub half_extents: Vector3D,
+}
+
+// Method for fitting an oriented bounding box.
+//      Pca: the axes are the principal directions of the surface (area-weighted). Fast, but a poor fit for shapes without clear principal directions (e.g., cubes).
+//      MinVolume: the smallest box with a face flush with a face of the convex hull, found with rotating calipers in the plane of every hull face.
+//          This is the optimal box for most shapes (O'Rourke, 1985, gives the exact but cubic-time algorithm), and never larger than the PCA box.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BoxFit {
+    #[default]
+    Pca,
+    MinVolume,
+}
+
+// Ball with center `center` and radius `radius`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BoundingSphere {
+    pub center: Vector3D,
+    pub radius: Float,
+}
+
+impl OrientedBox {
+    #[must_use]
+    pub fn volume(&self) -> Float {
+        8. * self.half_extents.product()
+    }
+
+    // Corners of the box, with corner i at the positive side of axis k if bit k of i is set.
+    #[must_use]
+    pub fn corners(&self) -> [Vector3D; 8] {
+        std::array::from_fn(|i| self.center + self.axes * Vector3D::from_fn(|k, _| if i >> k & 1 == 1 { 1. } else { -1. }).component_mul(&self.half_extents))
+    }
+
+    // Rigid transformation into the frame of the box, in which the box is axis-aligned and centered at the origin.
+    #[must_use]
+    pub fn to_canonical(&self) -> nalgebra::Isometry3<Float> {
+        let rotation = nalgebra::UnitQuaternion::from_rotation_matrix(&self.axes.inverse());
+        nalgebra::Isometry3::from_parts((-(rotation * self.center)).into(), rotation)
+    }
+}
+
+impl BoundingSphere {
+    // Similarity transformation into the frame of the sphere, in which the sphere is the unit sphere centered at the origin.
+    #[must_use]
+    pub fn to_canonical(&self) -> nalgebra::Similarity3<Float> {
+        let scaling = if self.radius > 0. { 1. / self.radius } else { 1. };
+        nalgebra::Similarity3::from_parts((-self.center * scaling).into(), nalgebra::UnitQuaternion::identity(), scaling)
+    }
+}
+
+impl<M: Tag> Mesh<M> {
+    // Oriented bounding box of the vertices (see `BoxFit`).
+    #[must_use]
+    pub fn oriented_bounding_box(&self, fit: BoxFit) -> OrientedBox {
+        let points = self.vert_ids().into_iter().map(|vert_id| self.position(vert_id)).collect_vec();
+        let pca = fit_box(&points, self.principal_directions());
+        match fit {
+            BoxFit::Pca => pca,
+            BoxFit::MinVolume => {
+                // Hulls of collinear points do not exist, but their PCA box is optimal.
+                let Ok((hull, _)) = Self::from_convex_hull(&points, true) else {
+                    return pca;
+                };
+                let corners = hull.vert_ids().into_iter().map(|vert_id| hull.position(vert_id)).collect_vec();
+                hull.face_ids()
+                    .into_iter()
+                    .map(|face_id| {
+                        let normal = hull.normal(face_id);
+                        let u = normal.cross(&Vector3D::ith(normal.iamin(), 1.)).normalize();
+                        let v = normal.cross(&u);
+                        let projected = corners.iter().map(|p| Vector2D::new(p.dot(&u), p.dot(&v))).collect_vec();
+                        let polygon = convex_polygon(&projected).into_iter().map(|i| projected[i]).collect_vec();
+                        let side = min_area_rectangle(&polygon);
+                        let axis = u * side.x + v * side.y;
+                        fit_box(&corners, nalgebra::Rotation3::from_basis_unchecked(&[axis, normal.cross(&axis), normal]))
+                    })
+                    .chain([pca])
+                    .min_by(|a, b| a.volume().total_cmp(&b.volume()))
+                    .unwrap()
+            }
+        }
+    }
+
+    // Smallest sphere that contains all vertices (Welzl, 1991), in the iterative form with the vertices in (deterministic) random order.
+    #[must_use]
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        let mut points = self.vert_ids().into_iter().map(|vert_id| self.position(vert_id)).collect_vec();
+        points.shuffle(&mut StdRng::seed_from_u64(0));
+        let tolerance = 1e-12 * geom::scale(&points);
+        let contains = |ball: &BoundingSphere, p: &Vector3D| (p - ball.center).norm() <= ball.radius + tolerance;
+
+        let mut ball = points.first().map(|&p| sphere_through(&[p])).unwrap_or_default();
+        for i in 1..points.len() {
+            if contains(&ball, &points[i]) {
+                continue;
+            }
+            ball = sphere_through(&[points[i]]);
+            for j in 0..i {
+                if contains(&ball, &points[j]) {
+                    continue;
+                }
+                ball = sphere_through(&[points[i], points[j]]);
+                for k in 0..j {
+                    if contains(&ball, &points[k]) {
+                        continue;
+                    }
+                    ball = sphere_through(&[points[i], points[j], points[k]]);
+                    for l in 0..k {
+                        if !contains(&ball, &points[l]) {
+                            ball = sphere_through(&[points[i], points[j], points[k], points[l]]);
+                        }
+                    }
+                }
+            }
+        }
+        ball
+    }
+
+    // Principal directions of the surface (the eigenvectors of its area-weighted covariance), by decreasing variance.
+    fn principal_directions(&self) -> nalgebra::Rotation3<Float> {
+        let (mut area, mut first, mut second) = (0., Vector3D::zeros(), nalgebra::Matrix3::zeros());
+        for [a, b, c] in self.fan_triangles() {
+            let size = (b - a).cross(&(c - a)).norm() / 2.;
+            let centroid = (a + b + c) / 3.;
+            area += size;
+            first += centroid * size;
+            second += (centroid * centroid.transpose() * 9. + a * a.transpose() + b * b.transpose() + c * c.transpose()) * size / 12.;
+        }
+        if area <= 0. {
+            return nalgebra::Rotation3::identity();
+        }
+        let mean = first / area;
+        let eigen = (second / area - mean * mean.transpose()).symmetric_eigen();
+        let order = (0..3).sorted_by(|&i, &j| eigen.eigenvalues[j].total_cmp(&eigen.eigenvalues[i])).collect_vec();
+        let (u, v) = (
+            eigen.eigenvectors.column(order[0]).into_owned(),
+            eigen.eigenvectors.column(order[1]).into_owned(),
+        );
+        nalgebra::Rotation3::from_basis_unchecked(&[u, v, u.cross(&v)])
+    }
+}
+
+// Smallest box with the given axes that contains the points.
+fn fit_box(points: &[Vector3D], axes: nalgebra::Rotation3<Float>) -> OrientedBox {
+    if points.is_empty() {
+        return OrientedBox {
+            center: Vector3D::zeros(),
+            axes,
+            half_extents: Vector3D::zeros(),
+        };
+    }
+    let local = points.iter().map(|p| axes.inverse() * p).collect_vec();
+    let min = local.iter().fold(Vector3D::repeat(INF), |min, p| min.inf(p));
+    let max = local.iter().fold(Vector3D::repeat(NEG_INF), |max, p| max.sup(p));
+    OrientedBox {
+        center: axes * ((min + max) / 2.),
+        axes,
+        half_extents: (max - min) / 2.,
+    }
+}
+
+// Direction of a side of the smallest rectangle around a convex polygon (in counterclockwise order), by rotating calipers:
+// one side of the smallest rectangle lies on an edge of the polygon, and the extreme points in the directions along and across the edges rotate with the edges.
+fn min_area_rectangle(polygon: &[Vector2D]) -> Vector2D {
+    let n = polygon.len();
+    if n < 3 {
+        return if n == 2 { (polygon[1] - polygon[0]).normalize() } else { Vector2D::x() };
+    }
+    let extreme = |d: Vector2D| (0..n).max_by(|&i, &j| polygon[i].dot(&d).total_cmp(&polygon[j].dot(&d))).unwrap();
+    let first = (polygon[1] - polygon[0]).normalize();
+    let (mut right, mut top, mut left) = (extreme(first), extreme(Vector2D::new(-first.y, first.x)), extreme(-first));
+    // Advance a caliper (at most once around the polygon) while the next corner is at least as far in the direction.
+    let advance = |mut i: usize, d: Vector2D| {
+        for _ in 0..n {
+            if polygon[(i + 1) % n].dot(&d) < polygon[i].dot(&d) {
+                break;
+            }
+            i = (i + 1) % n;
+        }
+        i
+    };
+
+    let mut best = (INF, first);
+    for i in 0..n {
+        let u = (polygon[(i + 1) % n] - polygon[i]).normalize();
+        let v = Vector2D::new(-u.y, u.x);
+        (right, top, left) = (advance(right, u), advance(top, v), advance(left, -u));
+        let area = (polygon[right] - polygon[left]).dot(&u) * (polygon[top] - polygon[i]).dot(&v);
+        if area < best.0 {
+            best = (area, u);
+        }
+    }
+    best.1
+}
+
+// Smallest sphere with the given (one to four) points on its boundary. Degenerate (collinear or coplanar) points get the smallest sphere around
+// the points that does exist.
+fn sphere_through(points: &[Vector3D]) -> BoundingSphere {
+    let a = points[0];
+    let center = match points[1..] {
+        [] => a,
+        [b] => (a + b) / 2.,
+        [b, c] => {
+            let (u, v) = (b - a, c - a);
+            let w = u.cross(&v);
+            if w.norm_squared() == 0. {
+                return farthest_pair(points);
+            }
+            a + (v.cross(&w) * u.norm_squared() + w.cross(&u) * v.norm_squared()) / (2. * w.norm_squared())
+        }
+        [b, c, d] => {
+            let matrix = nalgebra::Matrix3::from_rows(&[(b - a).transpose(), (c - a).transpose(), (d - a).transpose()]);
+            let rhs = Vector3D::new((b - a).norm_squared(), (c - a).norm_squared(), (d - a).norm_squared()) / 2.;
+            match matrix.lu().solve(&rhs) {
+                Some(offset) if offset.iter().all(|x| x.is_finite()) => a + offset,
+                _ => {
+                    let ball = sphere_through(&points[..3]);
+                    let radius = points.iter().map(|p| (p - ball.center).norm()).fold(0., Float::max);
+                    return BoundingSphere { center: ball.center, radius };
+                }
+            }
+        }
+        _ => unreachable!(),
+    };
+    let radius = points.iter().map(|p| (p - center).norm()).fold(0., Float::max);
+    BoundingSphere { center, radius }
+}
+
+fn farthest_pair(points: &[Vector3D]) -> BoundingSphere {
+    let (a, b) = points
+        .iter()
+        .tuple_combinations()
+        .max_by(|(a, b), (c, d)| (*a - *b).norm().total_cmp(&(*c - *d).norm()))
+        .unwrap();
+    sphere_through(&[*a, *b])
+}
@@ -0,0 +1,176 @@
+use crate::prelude::*;
+use std::collections::HashSet;
+
+// Principal curvatures and directions at a vertex, with k1 >= k2.
+// Curvatures are positive where the surface bends away from its normal (for example, everywhere on a sphere with outward normals).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PrincipalCurvature {
+    pub k1: Float,
+    pub k2: Float,
+    pub d1: Vector3D,
+    pub d2: Vector3D,
+}
+
+impl PrincipalCurvature {
+    // Shape index (Koenderink and van Doorn) in [-1, 1]: 1 for a cap, 0.5 for a ridge, 0 for a saddle, -0.5 for a rut and -1 for a cup.
+    // Undefined (NaN) for flat points.
+    #[must_use]
+    pub fn shape_index(&self) -> Float {
+        if self.k1 == 0. && self.k2 == 0. {
+            return Float::NAN;
+        }
+        2. / PI * (self.k1 + self.k2).atan2(self.k1 - self.k2)
+    }
+
+    // Curvedness (Koenderink and van Doorn): the magnitude of the curvature, regardless of the shape.
+    #[must_use]
+    pub fn curvedness(&self) -> Float {
+        self.k1.hypot(self.k2) / Float::sqrt(2.)
+    }
+}
+
+// Discrete curvature operators on triangle meshes, following Meyer et al. (2003) "Discrete Differential-Geometry Operators for Triangulated 2-Manifolds".
+// All operators return an error if the mesh has a face that is not a triangle.
+impl<M: Tag> Mesh<M> {
+    // Mixed Voronoi area of every vertex. Together, these tile the surface (without overlap).
+    pub fn mixed_areas(&self) -> Result<ids::SecMap<VERT, M, Float>, MeshError<M>> {
+        self.is_triangular()?;
+        Ok(self.vert_attribute(|vert_id| self.mixed_area(vert_id)))
+    }
+
+    // Mean curvature of every vertex, from the cotangent Laplacian of the positions.
+    pub fn mean_curvature(&self) -> Result<ids::SecMap<VERT, M, Float>, MeshError<M>> {
+        self.is_triangular()?;
+        Ok(self.vert_attribute(|vert_id| {
+            let laplacian = self.edges(vert_id).into_iter().fold(Vector3D::zeros(), |sum, edge_id| {
+                sum + (self.position(vert_id) - self.position(self.toor(edge_id))) * self.cotan_weight(edge_id)
+            });
+            laplacian.dot(&self.normal(vert_id)) / (4. * self.mixed_area(vert_id))
+        }))
+    }
+
+    // Gaussian curvature of every vertex: the angle defect, normalized by the mixed Voronoi area.
+    pub fn gaussian_curvature(&self) -> Result<ids::SecMap<VERT, M, Float>, MeshError<M>> {
+        self.is_triangular()?;
+        Ok(self.vert_attribute(|vert_id| self.defect(vert_id) / self.mixed_area(vert_id)))
+    }
+
+    // Principal curvatures and directions of every vertex, by fitting a quadric (height function over the tangent plane) to its two-ring.
+    pub fn principal_curvatures(&self) -> Result<ids::SecMap<VERT, M, PrincipalCurvature>, MeshError<M>> {
+        self.is_triangular()?;
+        Ok(self.vert_attribute(|vert_id| self.fit_quadric(vert_id)))
+    }
+
+    // Shape index of every vertex (see `PrincipalCurvature::shape_index`).
+    pub fn shape_index(&self) -> Result<ids::SecMap<VERT, M, Float>, MeshError<M>> {
+        let curvatures = self.principal_curvatures()?;
+        Ok(self.vert_attribute(|vert_id| curvatures.get_or_panic(vert_id).shape_index()))
+    }
+
+    // Curvedness of every vertex (see `PrincipalCurvature::curvedness`).
+    pub fn curvedness(&self) -> Result<ids::SecMap<VERT, M, Float>, MeshError<M>> {
+        let curvatures = self.principal_curvatures()?;
+        Ok(self.vert_attribute(|vert_id| curvatures.get_or_panic(vert_id).curvedness()))
+    }
+
+    // Cotangent of the angle opposite to the given edge, in the face of the edge.
+    #[must_use]
+    pub fn cotan(&self, edge_id: EdgeKey<M>) -> Float {
+        let next_id = self.next(edge_id);
+        let (u, v) = (-self.vector(next_id), self.vector(self.next(next_id)));
+        u.dot(&v) / u.cross(&v).norm()
+    }
+
+    // Cotangent weight of the given edge: the sum of the cotangents of the two angles opposite to it.
+    #[must_use]
+    pub fn cotan_weight(&self, edge_id: EdgeKey<M>) -> Float {
+        self.cotan(edge_id) + self.cotan(self.twin(edge_id))
+    }
+
+    // Mixed Voronoi area of the given vertex: the Voronoi area within non-obtuse triangles, and a fixed part of the area of obtuse triangles.
+    pub(crate) fn mixed_area(&self, vert_id: VertKey<M>) -> Float {
+        self.edges(vert_id)
+            .into_iter()
+            .map(|edge_id| {
+                // The triangle (p, q, r) with p the vertex, and its edges pq, qr and rp.
+                let (qr_id, rp_id) = (self.next(edge_id), self.next(self.next(edge_id)));
+                let area = self.size(self.face(edge_id));
+                let (pq, qr, rp) = (self.vector(edge_id), self.vector(qr_id), self.vector(rp_id));
+                if pq.dot(&-rp) < 0. {
+                    // Obtuse at the vertex
+                    area / 2.
+                } else if qr.dot(&-pq) < 0. || rp.dot(&-qr) < 0. {
+                    // Obtuse at another corner
+                    area / 4.
+                } else {
+                    (pq.norm_squared() * self.cotan(edge_id) + rp.norm_squared() * self.cotan(rp_id)) / 8.
+                }
+            })
+            .sum()
+    }
+
+    fn fit_quadric(&self, vert_id: VertKey<M>) -> PrincipalCurvature {
+        let origin = self.position(vert_id);
+        let normal = self.normal(vert_id);
+        let helper = if normal.x.abs() < 0.9 { Vector3D::x() } else { Vector3D::y() };
+        let t1 = (helper - normal * helper.dot(&normal)).normalize();
+        let t2 = normal.cross(&t1);
+
+        // The two-ring of the vertex, in local coordinates (x, y along the tangent plane, height along the normal).
+        let one_ring = self.neighbors(vert_id);
+        let ring = one_ring
+            .iter()
+            .flat_map(|&neighbor_id| self.neighbors(neighbor_id))
+            .chain(one_ring.iter().copied())
+            .filter(|&neighbor_id| neighbor_id != vert_id)
+            .collect::<HashSet<_>>();
+        let local = ring
+            .into_iter()
+            .map(|neighbor_id| self.position(neighbor_id) - origin)
+            .map(|d| (d.dot(&t1), d.dot(&t2), d.dot(&normal)))
+            .collect::<Vec<_>>();
+        if local.len() < 5 {
+            return PrincipalCurvature::default();
+        }
+
+        // Least-squares fit of h(x, y) = a x^2 + b xy + c y^2 + d x + e y.
+        let matrix = nalgebra::DMatrix::from_fn(local.len(), 5, |i, j| {
+            let (x, y, _) = local[i];
+            [x * x, x * y, y * y, x, y][j]
+        });
+        let heights = nalgebra::DVector::from_iterator(local.len(), local.iter().map(|&(_, _, h)| h));
+        let Ok(coefficients) = matrix.svd(true, true).solve(&heights, EPS) else {
+            return PrincipalCurvature::default();
+        };
+        let (a, b, c, d, e) = (coefficients[0], coefficients[1], coefficients[2], coefficients[3], coefficients[4]);
+
+        // Shape operator (in the tangent plane) from the first and second fundamental forms of the height function.
+        // The second fundamental form is negated, such that surfaces that bend away from the normal have positive curvature.
+        let first = nalgebra::Matrix2::new(1. + d * d, d * e, d * e, 1. + e * e);
+        let second = nalgebra::Matrix2::new(2. * a, b, b, 2. * c) / -(1. + d * d + e * e).sqrt();
+        let Some(inverse) = first.try_inverse() else {
+            return PrincipalCurvature::default();
+        };
+        let shape = inverse * second;
+
+        // The shape operator is self-adjoint with respect to the first fundamental form, so its eigenvalues are real.
+        let (trace, determinant) = (shape.trace(), shape.determinant());
+        let discriminant = (trace * trace / 4. - determinant).max(0.).sqrt();
+        let (k1, k2) = (trace / 2. + discriminant, trace / 2. - discriminant);
+
+        // Eigenvector of the largest eigenvalue (any direction at umbilic points).
+        let direction = |k: Float, fallback: Vector3D| {
+            let m = shape - nalgebra::Matrix2::identity() * k;
+            let v = if m.row(0).norm() >= m.row(1).norm() {
+                nalgebra::Vector2::new(-m[(0, 1)], m[(0, 0)])
+            } else {
+                nalgebra::Vector2::new(-m[(1, 1)], m[(1, 0)])
+            };
+            (t1 * v.x + t2 * v.y).try_normalize(EPS).unwrap_or(fallback)
+        };
+        let d1 = direction(k1, t1);
+        let d2 = normal.cross(&d1);
+
+        PrincipalCurvature { k1, k2, d1, d2 }
+    }
+}
@@ -0,0 +1,51 @@
+use crate::prelude::*;
+use itertools::Itertools;
+
+// Deviation of a surface from another surface: the distances from points of the first surface to their closest points on the second.
+//      max: the largest distance (the one-sided Hausdorff distance), over the samples and the vertices.
+//      mean, rms: the mean and root mean square distance over the surface (over the samples, which are distributed by area).
+//      vertices: the distance of every vertex.
+// The distance to a surface without faces is infinite.
+#[derive(Clone, Debug)]
+pub struct Deviation<M: Tag> {
+    pub max: Float,
+    pub mean: Float,
+    pub rms: Float,
+    pub vertices: ids::SecMap<VERT, M, Float>,
+}
+
+impl<M: Tag> Mesh<M> {
+    // Deviation of this surface from the other surface, from `nr_samples` stratified samples (see `Mesh::sample_stratified`) and the vertices.
+    #[must_use]
+    pub fn deviation<N: Tag>(&self, other: &Mesh<N>, nr_samples: usize, seed: u64) -> Deviation<M> {
+        let location = other.bvh();
+        let distance = |point: Vector3D| location.closest_point(point).map_or(INF, |closest| closest.distance);
+
+        let vertices = self.vert_attribute(|vert_id| distance(self.position(vert_id)));
+        let distances = self
+            .sample_stratified(nr_samples, seed)
+            .iter()
+            .map(|sample| distance(sample.point.position))
+            .collect_vec();
+        let (mean, rms) = if distances.is_empty() {
+            (0., 0.)
+        } else {
+            let n = distances.len() as Float;
+            (distances.iter().sum::<Float>() / n, (distances.iter().map(|d| d * d).sum::<Float>() / n).sqrt())
+        };
+        let max = distances.iter().chain(vertices.iter().map(|(_, d)| d)).copied().fold(0., Float::max);
+        Deviation { max, mean, rms, vertices }
+    }
+
+    // Symmetric Hausdorff distance: the largest deviation of either surface from the other (see `Mesh::deviation`).
+    #[must_use]
+    pub fn hausdorff_distance<N: Tag>(&self, other: &Mesh<N>, nr_samples: usize, seed: u64) -> Float {
+        self.deviation(other, nr_samples, seed).max.max(other.deviation(self, nr_samples, seed).max)
+    }
+
+    // Chamfer distance: the sum of the mean squared deviations of both surfaces from each other (see `Mesh::deviation`).
+    #[must_use]
+    pub fn chamfer_distance<N: Tag>(&self, other: &Mesh<N>, nr_samples: usize, seed: u64) -> Float {
+        self.deviation(other, nr_samples, seed).rms.powi(2) + other.deviation(self, nr_samples, seed).rms.powi(2)
+    }
+}
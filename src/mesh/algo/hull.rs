@@ -0,0 +1,268 @@
+use crate::prelude::*;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+
+// Vertex of the original mesh of every vertex of its convex hull.
+pub type VertOrigins<M> = ids::SecMap<VERT, M, VertKey<M>>;
+
+// Convex hulls (quickhull, Barber et al., 1996). All orientation tests are exact (see `geom::orient3d`), so points that lie on the hull
+// without being a corner of it (in the interior of a face or an edge) never become vertices of the hull.
+impl<M: Tag> Mesh<M> {
+    // Convex hull of the given points, as a closed triangle mesh with outward facing faces, and the input index of every hull vertex.
+    // With `merge_coplanar`, neighboring triangles in the same plane are merged into (convex) polygons.
+    // If all points lie in a plane, the hull is degenerate: a double-sided polygon (the front and back glued together along the rim), without volume,
+    // of which every face overlaps its opposite face (see `flat_hull`). If they lie on a line, there is no hull (an error).
+    pub fn from_convex_hull(points: &[Vector3D], merge_coplanar: bool) -> Result<(Self, ids::IdMap<VERT, M>), MeshError<M>> {
+        let faces = match simplex(points)? {
+            Simplex::Flat(polygon) => flat_hull(&polygon, merge_coplanar),
+            Simplex::Solid(corners) => {
+                let triangles = quickhull(points, corners);
+                if merge_coplanar {
+                    merge(points, &triangles)
+                } else {
+                    triangles.into_iter().map(|triangle| triangle.to_vec()).collect()
+                }
+            }
+        };
+        Self::from(&faces, points).map(|(mesh, vert_ids, _)| (mesh, vert_ids))
+    }
+
+    // Convex hull of the vertices of this mesh (see `Mesh::from_convex_hull`), with the vertex of this mesh of every hull vertex.
+    pub fn convex_hull(&self, merge_coplanar: bool) -> Result<(Self, VertOrigins<M>), MeshError<M>> {
+        let vert_ids = self.vert_ids();
+        let points = vert_ids.iter().map(|&vert_id| self.position(vert_id)).collect_vec();
+        let (hull, input) = Self::from_convex_hull(&points, merge_coplanar)?;
+        let mut origins = ids::SecMap::new();
+        for hull_vert_id in hull.vert_ids() {
+            origins.insert(hull_vert_id, vert_ids[*input.id(&hull_vert_id).unwrap()]);
+        }
+        Ok((hull, origins))
+    }
+}
+
+enum Simplex {
+    // Four points that span a tetrahedron (with the fourth point below the triangle of the first three).
+    Solid([usize; 4]),
+    // The corners of the convex hull of coplanar points, in order.
+    Flat(Vec<usize>),
+}
+
+// Initial simplex of the hull, spanned by points that are far apart.
+fn simplex<M: Tag>(points: &[Vector3D]) -> Result<Simplex, MeshError<M>> {
+    let collinear = |a: Vector3D, b: Vector3D, c: Vector3D| {
+        (0..3).all(|axis| geom::orient2d(geom::drop_axis(a, axis), geom::drop_axis(b, axis), geom::drop_axis(c, axis)) == 0.)
+    };
+    let farthest =
+        |candidates: Vec<usize>, measure: &dyn Fn(Vector3D) -> Float| candidates.into_iter().max_by(|&i, &j| measure(points[i]).total_cmp(&measure(points[j])));
+
+    if points.is_empty() {
+        return Err(MeshError::Unknown("convex hull of no points".to_owned()));
+    }
+    let a = 0;
+    let p = points[a];
+    let distinct = (0..points.len()).filter(|&i| points[i] != p).collect_vec();
+    let Some(b) = farthest(distinct, &|x| (x - p).norm_squared()) else {
+        return Err(MeshError::Unknown("convex hull of a single point".to_owned()));
+    };
+    let q = points[b];
+    let off_line = (0..points.len()).filter(|&i| !collinear(p, q, points[i])).collect_vec();
+    let Some(c) = farthest(off_line, &|x| (q - p).cross(&(x - p)).norm_squared()) else {
+        return Err(MeshError::Unknown("convex hull of collinear points".to_owned()));
+    };
+    let r = points[c];
+    let off_plane = (0..points.len()).filter(|&i| geom::orient3d(p, q, r, points[i]) != 0.).collect_vec();
+    let Some(d) = farthest(off_plane, &|x| geom::orient3d(p, q, r, x).abs()) else {
+        // All points are coplanar: the convex hull within their plane.
+        let axis = geom::dominant_axis((q - p).cross(&(r - p)));
+        let polygon = convex_polygon(&points.iter().map(|&x| geom::drop_axis(x, axis)).collect_vec());
+        return Ok(Simplex::Flat(polygon));
+    };
+
+    Ok(Simplex::Solid(if geom::orient3d(p, q, r, points[d]) < 0. {
+        [a, b, c, d]
+    } else {
+        [a, c, b, d]
+    }))
+}
+
+// Corners of the convex hull of points in the plane, in counterclockwise order (Andrew's monotone chain).
+pub(crate) fn convex_polygon(points: &[Vector2D]) -> Vec<usize> {
+    let sorted = (0..points.len())
+        .sorted_by(|&i, &j| points[i].x.total_cmp(&points[j].x).then(points[i].y.total_cmp(&points[j].y)))
+        .collect_vec();
+    let mut polygon: Vec<usize> = vec![];
+    for order in [sorted.clone(), sorted.into_iter().rev().collect()] {
+        let floor = polygon.len();
+        for i in order {
+            while polygon.len() >= floor + 2 && geom::orient2d(points[polygon[polygon.len() - 2]], points[polygon[polygon.len() - 1]], points[i]) <= 0. {
+                polygon.pop();
+            }
+            polygon.push(i);
+        }
+        // The last point of each chain is the first point of the other chain.
+        polygon.pop();
+    }
+    polygon
+}
+
+struct HullFace {
+    corners: [usize; 3],
+    // Points above this face (and not yet inside the hull).
+    outside: Vec<usize>,
+    alive: bool,
+}
+
+// Triangles of the convex hull of points that are not all coplanar, starting from a tetrahedron of the points.
+fn quickhull(points: &[Vector3D], [a, b, c, d]: [usize; 4]) -> Vec<[usize; 3]> {
+    let above = |[a, b, c]: [usize; 3], p: usize| geom::orient3d(points[a], points[b], points[c], points[p]);
+
+    let mut faces = vec![];
+    // The face of every (directed) edge of the hull.
+    let mut edges = HashMap::new();
+    let add_face = |faces: &mut Vec<HullFace>, edges: &mut HashMap<(usize, usize), usize>, corners: [usize; 3]| {
+        for i in 0..3 {
+            edges.insert((corners[i], corners[(i + 1) % 3]), faces.len());
+        }
+        faces.push(HullFace {
+            corners,
+            outside: vec![],
+            alive: true,
+        });
+        faces.len() - 1
+    };
+    let initial = [[a, b, c], [b, a, d], [c, b, d], [a, c, d]].map(|corners| add_face(&mut faces, &mut edges, corners));
+    for p in (0..points.len()).filter(|p| ![a, b, c, d].contains(p)) {
+        if let Some(&face) = initial.iter().find(|&&face| above(faces[face].corners, p) > 0.) {
+            faces[face].outside.push(p);
+        }
+    }
+
+    let mut stack = initial.to_vec();
+    while let Some(face) = stack.pop() {
+        if !faces[face].alive || faces[face].outside.is_empty() {
+            continue;
+        }
+        // The point farthest above the face is a corner of the hull.
+        let corners = faces[face].corners;
+        let eye = faces[face]
+            .outside
+            .iter()
+            .copied()
+            .max_by(|&p, &q| above(corners, p).total_cmp(&above(corners, q)))
+            .unwrap();
+
+        // The faces that the eye sees form a connected region, which is bounded by the horizon. Faces in the plane of the eye are part of it,
+        // such that their corners that end up inside a face or an edge of the hull (in the same plane as the eye) are removed.
+        let mut visible = vec![face];
+        let mut seen = HashSet::from([face]);
+        let mut horizon = vec![];
+        let mut i = 0;
+        while i < visible.len() {
+            let corners = faces[visible[i]].corners;
+            for j in 0..3 {
+                let (u, v) = (corners[j], corners[(j + 1) % 3]);
+                let neighbor = edges[&(v, u)];
+                if seen.contains(&neighbor) {
+                    continue;
+                }
+                if above(faces[neighbor].corners, eye) >= 0. {
+                    seen.insert(neighbor);
+                    visible.push(neighbor);
+                } else {
+                    horizon.push((u, v));
+                }
+            }
+            i += 1;
+        }
+
+        // Replace the visible faces by a cone of faces from the horizon to the eye.
+        let mut orphans = vec![];
+        for &face in &visible {
+            faces[face].alive = false;
+            orphans.append(&mut faces[face].outside);
+            let corners = faces[face].corners;
+            for j in 0..3 {
+                edges.remove(&(corners[j], corners[(j + 1) % 3]));
+            }
+        }
+        let cone = horizon.into_iter().map(|(u, v)| add_face(&mut faces, &mut edges, [u, v, eye])).collect_vec();
+        // Points that were outside a visible face are either inside the new hull, or above one of the new faces.
+        for p in orphans.into_iter().filter(|&p| p != eye) {
+            if let Some(&face) = cone.iter().find(|&&face| above(faces[face].corners, p) > 0.) {
+                faces[face].outside.push(p);
+            }
+        }
+        stack.extend(cone);
+    }
+
+    faces.into_iter().filter(|face| face.alive).map(|face| face.corners).collect()
+}
+
+// Merge neighboring triangles (of a convex hull) in the same plane into polygons.
+fn merge(points: &[Vector3D], triangles: &[[usize; 3]]) -> Vec<Vec<usize>> {
+    let mut edges = HashMap::new();
+    for (t, triangle) in triangles.iter().enumerate() {
+        for i in 0..3 {
+            edges.insert((triangle[i], triangle[(i + 1) % 3]), t);
+        }
+    }
+    // Groups of coplanar triangles (union-find).
+    let mut parent = (0..triangles.len()).collect_vec();
+    fn root(parent: &mut [usize], t: usize) -> usize {
+        if parent[t] != t {
+            parent[t] = root(parent, parent[t]);
+        }
+        parent[t]
+    }
+    for (t, &[a, b, c]) in triangles.iter().enumerate() {
+        for (u, v) in [(a, b), (b, c), (c, a)] {
+            let neighbor = edges[&(v, u)];
+            let opposite = triangles[neighbor].into_iter().find(|&w| w != u && w != v).unwrap();
+            if geom::orient3d(points[a], points[b], points[c], points[opposite]) == 0. {
+                let (r, s) = (root(&mut parent, t), root(&mut parent, neighbor));
+                parent[r] = s;
+            }
+        }
+    }
+
+    // Every group is a convex polygon, bounded by the edges between groups.
+    let mut boundaries: HashMap<usize, HashMap<usize, usize>> = HashMap::new();
+    let mut order = vec![];
+    for (t, triangle) in triangles.iter().enumerate() {
+        let group = root(&mut parent, t);
+        if !boundaries.contains_key(&group) {
+            order.push(group);
+        }
+        let boundary = boundaries.entry(group).or_default();
+        for i in 0..3 {
+            let (u, v) = (triangle[i], triangle[(i + 1) % 3]);
+            if root(&mut parent, edges[&(v, u)]) != group {
+                boundary.insert(u, v);
+            }
+        }
+    }
+    order
+        .into_iter()
+        .map(|group| {
+            let boundary = &boundaries[&group];
+            let start = *boundary.keys().min().unwrap();
+            let mut polygon = vec![start];
+            while boundary[polygon.last().unwrap()] != start {
+                polygon.push(boundary[polygon.last().unwrap()]);
+            }
+            polygon
+        })
+        .collect()
+}
+
+// Double-sided polygon, glued together along its rim: either two polygons, or two triangle fans from different corners (such that they share no diagonals).
+// This is a closed mesh, but a degenerate one: it has no volume, and the front and back faces overlap (see `Mesh::self_intersections`).
+fn flat_hull(polygon: &[usize], merge_coplanar: bool) -> Vec<Vec<usize>> {
+    let n = polygon.len();
+    if merge_coplanar {
+        return vec![polygon.to_vec(), polygon.iter().rev().copied().collect()];
+    }
+    let front = (1..n - 1).map(|i| vec![polygon[0], polygon[i], polygon[i + 1]]);
+    let back = (2..n).map(|i| vec![polygon[1], polygon[(i + 1) % n], polygon[i]]);
+    front.chain(back).collect()
+}
@@ -0,0 +1,90 @@
+use crate::prelude::*;
+
+// Global integral properties of the (closed) mesh, and of the solid it encloses (with unit density).
+// Polygonal faces are split into a fan of triangles around their first corner, such that non-planar faces still close the surface.
+impl<M: Tag> Mesh<M> {
+    // Total surface area.
+    #[must_use]
+    pub fn surface_area(&self) -> Float {
+        self.face_ids().into_iter().map(|face_id| self.size(face_id)).sum()
+    }
+
+    // Signed enclosed volume (divergence theorem). Positive if the faces are oriented outwards.
+    #[must_use]
+    pub fn volume(&self) -> Float {
+        self.fan_triangles().into_iter().map(|[a, b, c]| a.dot(&b.cross(&c))).sum::<Float>() / 6.
+    }
+
+    // Centroid of the surface (of the faces, weighted by area).
+    #[must_use]
+    pub fn area_centroid(&self) -> Vector3D {
+        let (moment, area) = self.fan_triangles().into_iter().fold((Vector3D::zeros(), 0.), |(moment, area), [a, b, c]| {
+            let size = (b - a).cross(&(c - a)).norm() / 2.;
+            (moment + (a + b + c) / 3. * size, area + size)
+        });
+        self.center() + moment / area
+    }
+
+    // Centroid of the enclosed solid. Undefined (NaN) if the enclosed volume is zero.
+    #[must_use]
+    pub fn volume_centroid(&self) -> Vector3D {
+        let (moment, volume) = self.fan_triangles().into_iter().fold((Vector3D::zeros(), 0.), |(moment, volume), [a, b, c]| {
+            // Tetrahedron spanned by the triangle and the reference point.
+            let size = a.dot(&b.cross(&c)) / 6.;
+            (moment + (a + b + c) / 4. * size, volume + size)
+        });
+        self.center() + moment / volume
+    }
+
+    // Inertia tensor of the enclosed solid (with unit density), with respect to its volume centroid.
+    #[must_use]
+    pub fn inertia_tensor(&self) -> nalgebra::Matrix3<Float> {
+        // Second moment (covariance) of every tetrahedron spanned by a triangle and the reference point, by transforming the canonical tetrahedron.
+        let canonical = nalgebra::Matrix3::new(2., 1., 1., 1., 2., 1., 1., 1., 2.) / 120.;
+        let (covariance, moment, volume) = self.fan_triangles().into_iter().fold(
+            (nalgebra::Matrix3::zeros(), Vector3D::zeros(), 0.),
+            |(covariance, moment, volume), [a, b, c]| {
+                let transform = nalgebra::Matrix3::from_columns(&[a, b, c]);
+                let determinant = a.dot(&b.cross(&c));
+                (
+                    covariance + transform * canonical * transform.transpose() * determinant,
+                    moment + (a + b + c) / 4. * determinant / 6.,
+                    volume + determinant / 6.,
+                )
+            },
+        );
+
+        // Move from the reference point to the centroid (parallel axis theorem).
+        let centroid = moment / volume;
+        let covariance = covariance - centroid * centroid.transpose() * volume;
+        nalgebra::Matrix3::identity() * covariance.trace() - covariance
+    }
+
+    // Principal moments of inertia and the corresponding axes (orthonormal, right-handed), sorted by increasing moment.
+    #[must_use]
+    pub fn principal_axes(&self) -> [(Float, Vector3D); 3] {
+        let eigen = self.inertia_tensor().symmetric_eigen();
+        let mut order = [0, 1, 2];
+        order.sort_by(|&i, &j| eigen.eigenvalues[i].total_cmp(&eigen.eigenvalues[j]));
+        let [first, second, _] = order.map(|i| (eigen.eigenvalues[i], eigen.eigenvectors.column(i).into_owned()));
+        [first, second, (eigen.eigenvalues[order[2]], first.1.cross(&second.1))]
+    }
+
+    // Triangles of the faces (as fans), relative to the center of the bounding box to limit cancellation.
+    pub(crate) fn fan_triangles(&self) -> Vec<[Vector3D; 3]> {
+        let reference = self.center();
+        self.face_ids()
+            .into_iter()
+            .flat_map(|face_id| {
+                let corners = self
+                    .vertices(face_id)
+                    .into_iter()
+                    .map(|vert_id| self.position(vert_id) - reference)
+                    .collect::<Vec<_>>();
+                (1..corners.len() - 1)
+                    .map(move |i| [corners[0], corners[i], corners[i + 1]])
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,210 @@
+use crate::prelude::*;
+use geom::TriangleIntersection;
+use itertools::Itertools;
+use std::collections::HashMap;
+
+// Intersections between faces, within a mesh (self-intersections) or between two meshes.
+// Candidate pairs are found with the bounding volume hierarchy (see `Mesh::bvh`), and polygonal faces are tested by their triangles.
+impl<M: Tag> Mesh<M> {
+    // Pairs of faces that intersect each other, every pair once. Neighboring faces (sharing a vertex or an edge) are only reported
+    // if they intersect away from what they share, for example if they fold over each other.
+    #[must_use]
+    pub fn self_intersections(&self) -> Vec<(FaceKey<M>, FaceKey<M>)> {
+        self.self_intersecting_triangles()
+            .into_iter()
+            .map(|(a, b, _)| if a.to_bits() <= b.to_bits() { (a, b) } else { (b, a) })
+            .unique()
+            .collect()
+    }
+
+    // Curves along which faces intersect each other, as polylines (closed polylines end at their first point). Coplanar overlaps are not included.
+    #[must_use]
+    pub fn self_intersection_curves(&self) -> Vec<Vec<Vector3D>> {
+        polylines(&segments(self.self_intersecting_triangles()))
+    }
+
+    // Pairs of faces of this mesh and the other mesh that intersect each other, every pair once.
+    #[must_use]
+    pub fn intersections_with<N: Tag>(&self, other: &Mesh<N>) -> Vec<(FaceKey<M>, FaceKey<N>)> {
+        self.intersecting_triangles(other).into_iter().map(|(a, b, _)| (a, b)).unique().collect()
+    }
+
+    // Curves along which the faces of this mesh intersect the faces of the other mesh, as polylines (see `Mesh::self_intersection_curves`).
+    #[must_use]
+    pub fn intersection_curves_with<N: Tag>(&self, other: &Mesh<N>) -> Vec<Vec<Vector3D>> {
+        polylines(&segments(self.intersecting_triangles(other)))
+    }
+
+    fn self_intersecting_triangles(&self) -> Vec<(FaceKey<M>, FaceKey<M>, TriangleIntersection)> {
+        let location = self.bvh();
+        let mut intersections = vec![];
+        for i in 0..location.nr_triangles() {
+            let (t, t_corners, t_face) = location.triangle(i);
+            for j in location.overlapping(t) {
+                let (u, u_corners, u_face) = location.triangle(j);
+                if j <= i || t_face == u_face {
+                    continue;
+                }
+                let (t_verts, u_verts) = (self.vertices(t_face), self.vertices(u_face));
+                let intersection = intersection_of_neighbors(t, t_corners.map(|c| t_verts[c]), u, u_corners.map(|c| u_verts[c]));
+                if let Some(intersection) = intersection {
+                    intersections.push((t_face, u_face, intersection));
+                }
+            }
+        }
+        intersections
+    }
+
+    fn intersecting_triangles<N: Tag>(&self, other: &Mesh<N>) -> Vec<(FaceKey<M>, FaceKey<N>, TriangleIntersection)> {
+        let (location, other_location) = (self.bvh(), other.bvh());
+        let mut intersections = vec![];
+        for i in 0..location.nr_triangles() {
+            let (t, _, t_face) = location.triangle(i);
+            for j in other_location.overlapping(t) {
+                let (u, _, u_face) = other_location.triangle(j);
+                if let Some(intersection) = geom::triangle_triangle_intersection((t[0], t[1], t[2]), (u[0], u[1], u[2])) {
+                    intersections.push((t_face, u_face, intersection));
+                }
+            }
+        }
+        intersections
+    }
+}
+
+// Intersection of triangles `t` and `u` of the same mesh (with vertices `t_verts` and `u_verts`), ignoring the vertex or edge they share.
+fn intersection_of_neighbors<M: Tag>(t: [Vector3D; 3], t_verts: [VertKey<M>; 3], u: [Vector3D; 3], u_verts: [VertKey<M>; 3]) -> Option<TriangleIntersection> {
+    let shared = (0..3).filter(|&i| u_verts.contains(&t_verts[i])).collect_vec();
+    let intersection = geom::triangle_triangle_intersection((t[0], t[1], t[2]), (u[0], u[1], u[2]));
+    let axis = geom::dominant_axis((t[1] - t[0]).cross(&(t[2] - t[0])));
+    let project = |p: Vector3D| geom::drop_axis(p, axis);
+    match shared.len() {
+        0 => intersection,
+        1 => {
+            let i = shared[0];
+            let j = u_verts.iter().position(|&vert_id| vert_id == t_verts[i]).unwrap();
+            match intersection? {
+                // Triangles in different planes always touch at the shared vertex.
+                TriangleIntersection::Segment(a, b) => {
+                    let tolerance = geom::tolerance(geom::scale(t.iter().chain(&u)));
+                    ((a - t[i]).norm() > tolerance || (b - t[i]).norm() > tolerance).then_some(TriangleIntersection::Segment(a, b))
+                }
+                // Coplanar triangles overlap if their angles at the shared vertex do.
+                TriangleIntersection::Coplanar => wedges_overlap(
+                    project(t[i]),
+                    [project(t[(i + 1) % 3]), project(t[(i + 2) % 3])],
+                    [project(u[(j + 1) % 3]), project(u[(j + 2) % 3])],
+                )
+                .then_some(TriangleIntersection::Coplanar),
+            }
+        }
+        2 => {
+            // Triangles sharing an edge only overlap if they are coplanar and on the same side of the edge.
+            let (a, b) = (t[shared[0]], t[shared[1]]);
+            let t_opposite = t[(0..3).find(|i| !shared.contains(i)).unwrap()];
+            let u_opposite = u[(0..3).find(|&j| !t_verts.contains(&u_verts[j])).unwrap()];
+            if geom::orient3d(t[0], t[1], t[2], u_opposite) != 0. {
+                return None;
+            }
+            let (t_side, u_side) = (
+                geom::orient2d(project(a), project(b), project(t_opposite)),
+                geom::orient2d(project(a), project(b), project(u_opposite)),
+            );
+            (t_side * u_side > 0.).then_some(TriangleIntersection::Coplanar)
+        }
+        // Duplicate triangles.
+        _ => Some(TriangleIntersection::Coplanar),
+    }
+}
+
+// Whether the angles at `o` spanned by `t` and by `u` (both less than 180 degrees) overlap.
+fn wedges_overlap(o: Vector2D, t: [Vector2D; 2], u: [Vector2D; 2]) -> bool {
+    let strictly_inside = |p: Vector2D, [a, b]: [Vector2D; 2]| {
+        let side = geom::orient2d(o, a, b).signum();
+        geom::orient2d(o, a, p) * side > 0. && geom::orient2d(o, p, b) * side > 0.
+    };
+    let same_direction = |p: Vector2D, q: Vector2D| geom::orient2d(o, p, q) == 0. && (p - o).dot(&(q - o)) > 0.;
+    t.iter().any(|&p| strictly_inside(p, u))
+        || u.iter().any(|&p| strictly_inside(p, t))
+        || (same_direction(t[0], u[0]) && same_direction(t[1], u[1]))
+        || (same_direction(t[0], u[1]) && same_direction(t[1], u[0]))
+}
+
+fn segments<A, B>(intersections: Vec<(A, B, TriangleIntersection)>) -> Vec<(Vector3D, Vector3D)> {
+    intersections
+        .into_iter()
+        .filter_map(|(_, _, intersection)| match intersection {
+            TriangleIntersection::Segment(a, b) => Some((a, b)),
+            TriangleIntersection::Coplanar => None,
+        })
+        .collect()
+}
+
+// Chain segments into polylines. Endpoints closer than a small tolerance (relative to the coordinates) are merged.
+// Open polylines start at points where an odd number of segments meet, and the remaining segments form closed polylines.
+fn polylines(segments: &[(Vector3D, Vector3D)]) -> Vec<Vec<Vector3D>> {
+    let mut welder = Welder::new(1e-9 * geom::scale(segments.iter().flat_map(|(a, b)| [a, b])));
+    let edges = segments
+        .iter()
+        .map(|&(a, b)| (welder.insert(a), welder.insert(b)))
+        .filter(|(a, b)| a != b)
+        .map(|(a, b)| (a.min(b), a.max(b)))
+        .unique()
+        .collect_vec();
+
+    let points = welder.points;
+    let mut adjacency = vec![vec![]; points.len()];
+    for (index, &(a, b)) in edges.iter().enumerate() {
+        adjacency[a].push(index);
+        adjacency[b].push(index);
+    }
+    let mut used = vec![false; edges.len()];
+    let starts = (0..points.len()).filter(|&p| adjacency[p].len() % 2 == 1).chain(0..points.len()).collect_vec();
+    let mut polylines = vec![];
+    for start in starts {
+        while adjacency[start].iter().any(|&index| !used[index]) {
+            let mut polyline = vec![points[start]];
+            let mut current = start;
+            while let Some(&index) = adjacency[current].iter().find(|&&index| !used[index]) {
+                used[index] = true;
+                current = if edges[index].0 == current { edges[index].1 } else { edges[index].0 };
+                polyline.push(points[current]);
+            }
+            polylines.push(polyline);
+        }
+    }
+    polylines
+}
+
+// Merges points closer than `tolerance` to each other, by looking for nearby points in a grid of cells of the size of the tolerance.
+pub(crate) struct Welder {
+    pub(crate) points: Vec<Vector3D>,
+    grid: HashMap<[i64; 3], Vec<usize>>,
+    tolerance: f64,
+}
+
+impl Welder {
+    pub(crate) fn new(tolerance: f64) -> Self {
+        Self {
+            points: vec![],
+            grid: HashMap::new(),
+            tolerance: tolerance.max(f64::MIN_POSITIVE),
+        }
+    }
+
+    // Index of the given point, or of an earlier point within the tolerance.
+    #[allow(clippy::cast_possible_truncation)]
+    pub(crate) fn insert(&mut self, p: Vector3D) -> usize {
+        let cell = [0, 1, 2].map(|axis| (p[axis] / self.tolerance).floor() as i64);
+        let existing = (0..27)
+            .map(|i| [cell[0] + i % 3 - 1, cell[1] + i / 3 % 3 - 1, cell[2] + i / 9 - 1])
+            .filter_map(|neighbor| self.grid.get(&neighbor))
+            .flatten()
+            .find(|&&index| (self.points[index] - p).norm() <= self.tolerance)
+            .copied();
+        existing.unwrap_or_else(|| {
+            self.points.push(p);
+            self.grid.entry(cell).or_default().push(self.points.len() - 1);
+            self.points.len() - 1
+        })
+    }
+}
@@ -0,0 +1,278 @@
+use crate::prelude::*;
+use itertools::Itertools;
+use std::collections::HashMap;
+
+// Method for extracting an isosurface from a scalar field.
+//      MarchingCubes: vertices on the edges of the grid where the field crosses the isovalue. Ambiguous faces of the cells are resolved with the asymptotic decider
+//          (Nielson and Hamann, 1991), such that neighboring cells agree, and the result is always a closed 2-manifold.
+//      DualContouring: a vertex in every cell that the surface passes through, placed with the gradients of the field, which preserves sharp features (Ju et al., 2002).
+//          Cells that the surface passes through more than once get a single vertex, which can make the result non-manifold (an error).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Isosurface {
+    #[default]
+    MarchingCubes,
+    DualContouring,
+}
+
+// Grid edge from a sample (i, j, k) to the next sample along an axis.
+type GridEdge = ([i64; 3], usize);
+
+impl<M: Tag> Mesh<M> {
+    // Isosurface (where the field equals `iso`) of a field sampled on a grid, with the normals pointing towards larger values.
+    // Samples outside the grid count as larger than `iso`, such that the surface is closed (it is capped at the boundary of the grid).
+    pub fn from_grid(grid: &Grid<Float>, iso: Float, method: Isosurface) -> Result<Self, MeshError<M>> {
+        let field = Field::new(grid, iso)?;
+        match method {
+            Isosurface::MarchingCubes => field.marching_cubes(),
+            Isosurface::DualContouring => field.dual_contouring(|point| field.gradient(point)),
+        }
+    }
+
+    // Isosurface of the field `f`, sampled on a grid spanning `bbox` = (min, max) with `resolution` samples along each axis (see `Mesh::from_grid`).
+    // For dual contouring, the gradients are taken from `f` (by central differences) instead of from the samples.
+    pub fn from_implicit(
+        f: impl Fn(Vector3D) -> Float + Sync,
+        bbox: (Vector3D, Vector3D),
+        resolution: [usize; 3],
+        iso: Float,
+        method: Isosurface,
+    ) -> Result<Self, MeshError<M>> {
+        let grid = Grid::from_fn(bbox, resolution, &f);
+        let field = Field::new(&grid, iso)?;
+        match method {
+            Isosurface::MarchingCubes => field.marching_cubes(),
+            Isosurface::DualContouring => {
+                let h = 1e-3 * grid.spacing.min();
+                field.dual_contouring(|point| Vector3D::from_fn(|axis, _| (f(point + Vector3D::ith(axis, h)) - f(point - Vector3D::ith(axis, h))) / (2. * h)))
+            }
+        }
+    }
+}
+
+// Sampled field, relative to the isovalue (negative inside), and padded with a layer of positive samples around the grid.
+struct Field<'a> {
+    grid: &'a Grid<Float>,
+    iso: Float,
+    padding: Float,
+}
+
+impl<'a> Field<'a> {
+    fn new<M: Tag>(grid: &'a Grid<Float>, iso: Float) -> Result<Self, MeshError<M>> {
+        if grid.resolution.iter().any(|&n| n < 2) {
+            return Err(MeshError::Unknown("grid needs at least two samples along every axis".to_string()));
+        }
+        let padding = grid.values.iter().map(|value| (value - iso).abs()).fold(0., Float::max);
+        Ok(Self {
+            grid,
+            iso,
+            padding: if padding > 0. { padding } else { 1. },
+        })
+    }
+
+    #[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+    fn value(&self, sample: [i64; 3]) -> Float {
+        if (0..3).any(|axis| sample[axis] < 0 || sample[axis] >= self.grid.resolution[axis] as i64) {
+            return self.padding;
+        }
+        self.grid.values[self.grid.index(sample.map(|i| i as usize))] - self.iso
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn position(&self, sample: [i64; 3]) -> Vector3D {
+        self.grid.origin
+            + self
+                .grid
+                .spacing
+                .component_mul(&Vector3D::new(sample[0] as f64, sample[1] as f64, sample[2] as f64))
+    }
+
+    // Lower corners of all cells, including those in the padding.
+    #[allow(clippy::cast_possible_wrap)]
+    fn cells(&self) -> impl Iterator<Item = [i64; 3]> {
+        let [nx, ny, nz] = self.grid.resolution.map(|n| n as i64);
+        itertools::iproduct!(-1..nz, -1..ny, -1..nx).map(|(k, j, i)| [i, j, k])
+    }
+
+    fn is_crossed(&self, (sample, axis): GridEdge) -> bool {
+        (self.value(sample) < 0.) != (self.value(offset(sample, axis, 1)) < 0.)
+    }
+
+    // Point where the field crosses zero on a grid edge (by linear interpolation).
+    fn crossing(&self, (sample, axis): GridEdge) -> Vector3D {
+        let next = offset(sample, axis, 1);
+        let (a, b) = (self.value(sample), self.value(next));
+        let t = a / (a - b);
+        self.position(sample) * (1. - t) + self.position(next) * t
+    }
+
+    // Gradient of the field, by trilinear interpolation of central differences at the samples.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn gradient(&self, point: Vector3D) -> Vector3D {
+        let local = (point - self.grid.origin).component_div(&self.grid.spacing);
+        let cell: [i64; 3] = std::array::from_fn(|axis| (local[axis].floor() as i64).clamp(-1, self.grid.resolution[axis] as i64 - 1));
+        let t = Vector3D::from_fn(|axis, _| (local[axis] - cell[axis] as f64).clamp(0., 1.));
+        let mut gradient = Vector3D::zeros();
+        for corner in 0..8 {
+            let sample = std::array::from_fn(|axis| cell[axis] + i64::from(corner >> axis & 1 == 1));
+            let weight = (0..3)
+                .map(|axis| if corner >> axis & 1 == 1 { t[axis] } else { 1. - t[axis] })
+                .product::<Float>();
+            let difference =
+                Vector3D::from_fn(|axis, _| (self.value(offset(sample, axis, 1)) - self.value(offset(sample, axis, -1))) / (2. * self.grid.spacing[axis]));
+            gradient += difference * weight;
+        }
+        gradient
+    }
+
+    fn marching_cubes<M: Tag>(&self) -> Result<Mesh<M>, MeshError<M>> {
+        let mut vertices = HashMap::<GridEdge, usize>::new();
+        let mut positions = vec![];
+        let mut faces = vec![];
+        for cell in self.cells() {
+            let corners = (0..8)
+                .map(|corner| std::array::from_fn(|axis| cell[axis] + i64::from(corner >> axis & 1 == 1)))
+                .collect_vec();
+            let nr_inside = corners.iter().filter(|&&sample| self.value(sample) < 0.).count();
+            if nr_inside == 0 || nr_inside == 8 {
+                continue;
+            }
+
+            // On every face of the cell, the surface runs from an edge where it enters (going counterclockwise, seen from outside the cell) to an edge where it leaves.
+            let mut next = HashMap::<GridEdge, GridEdge>::new();
+            let mut ambiguous = false;
+            for (axis, side) in itertools::iproduct!(0..3, 0..2) {
+                let (b, c) = ((axis + 1) % 3, (axis + 2) % 3);
+                let square = if side == 1 {
+                    [(0, 0), (1, 0), (1, 1), (0, 1)]
+                } else {
+                    [(0, 0), (0, 1), (1, 1), (1, 0)]
+                };
+                let quad = square.map(|(u, v)| {
+                    let mut sample = cell;
+                    sample[axis] += side;
+                    sample[b] += u;
+                    sample[c] += v;
+                    sample
+                });
+                let w = quad.map(|sample| self.value(sample));
+                let edge = |i: usize| grid_edge(quad[i], quad[(i + 1) % 4]);
+                let enters = (0..4).filter(|&i| w[i] >= 0. && w[(i + 1) % 4] < 0.).collect_vec();
+                let leaves = (0..4).filter(|&i| w[i] < 0. && w[(i + 1) % 4] >= 0.).collect_vec();
+                if enters.len() == 1 {
+                    next.insert(edge(enters[0]), edge(leaves[0]));
+                } else if enters.len() == 2 {
+                    // Alternating corners: the surface either separates the inside corners, or connects them (if the saddle point of the bilinear interpolant is inside).
+                    let connected = (w[0] * w[2] - w[1] * w[3]) / (w[0] + w[2] - w[1] - w[3]) < 0.;
+                    ambiguous = true;
+                    for &i in &enters {
+                        next.insert(edge(i), edge(if connected { (i + 3) % 4 } else { (i + 1) % 4 }));
+                    }
+                }
+            }
+
+            // Chain the segments into loops, and triangulate them as fans. A loop can visit both segments on an ambiguous face, and a diagonal between them
+            // could then also be used by the neighboring cell, so such loops are triangulated around a vertex in their center instead.
+            while let Some(&start) = next.keys().next() {
+                let mut polygon = vec![];
+                let mut current = start;
+                while let Some(following) = next.remove(&current) {
+                    let index = *vertices.entry(current).or_insert_with(|| {
+                        positions.push(self.crossing(current));
+                        positions.len() - 1
+                    });
+                    polygon.push(index);
+                    current = following;
+                }
+                if ambiguous && polygon.len() > 3 {
+                    positions.push(polygon.iter().map(|&index| positions[index]).sum::<Vector3D>() / polygon.len() as f64);
+                    for i in 0..polygon.len() {
+                        faces.push(vec![positions.len() - 1, polygon[i], polygon[(i + 1) % polygon.len()]]);
+                    }
+                } else {
+                    for i in 1..polygon.len() - 1 {
+                        faces.push(vec![polygon[0], polygon[i], polygon[i + 1]]);
+                    }
+                }
+            }
+        }
+        Mesh::from(&faces, &positions).map(|(mesh, _, _)| mesh)
+    }
+
+    fn dual_contouring<M: Tag>(&self, gradient: impl Fn(Vector3D) -> Vector3D) -> Result<Mesh<M>, MeshError<M>> {
+        // A vertex in every cell with crossed edges, minimizing the distances to the tangent planes at the crossings (the quadratic error function).
+        let mut vertices = HashMap::<[i64; 3], usize>::new();
+        let mut positions = vec![];
+        for cell in self.cells() {
+            let crossings = itertools::iproduct!(0..3, 0..2, 0..2)
+                .map(|(axis, u, v)| {
+                    let mut sample = cell;
+                    sample[(axis + 1) % 3] += u;
+                    sample[(axis + 2) % 3] += v;
+                    (sample, axis)
+                })
+                .filter(|&edge| self.is_crossed(edge))
+                .map(|edge| {
+                    let point = self.crossing(edge);
+                    (point, gradient(point).try_normalize(0.).unwrap_or_default())
+                })
+                .collect_vec();
+            if crossings.is_empty() {
+                continue;
+            }
+
+            // Solved around the mean of the crossings, ignoring directions in which the planes do not constrain the vertex (small eigenvalues).
+            let mean = crossings.iter().map(|(point, _)| point).sum::<Vector3D>() / crossings.len() as f64;
+            let (matrix, rhs) = crossings
+                .iter()
+                .fold((nalgebra::Matrix3::zeros(), Vector3D::zeros()), |(matrix, rhs), (point, normal)| {
+                    (matrix + normal * normal.transpose(), rhs + normal * normal.dot(&(point - mean)))
+                });
+            let eigen = matrix.symmetric_eigen();
+            let largest = eigen.eigenvalues.max();
+            let mut position = mean;
+            for i in 0..3 {
+                if eigen.eigenvalues[i] > 0.1 * largest {
+                    let direction = eigen.eigenvectors.column(i);
+                    position += direction * direction.dot(&rhs) / eigen.eigenvalues[i];
+                }
+            }
+            // Clamped to the cell.
+            let (low, high) = (self.position(cell), self.position(cell.map(|i| i + 1)));
+            let position = Vector3D::from_fn(|axis, _| position[axis].clamp(low[axis].min(high[axis]), low[axis].max(high[axis])));
+            vertices.insert(cell, positions.len());
+            positions.push(position);
+        }
+
+        // A quad around every crossed edge, connecting the vertices of the four cells around it (split into two triangles along its shorter diagonal).
+        let mut faces = vec![];
+        for (cell, axis) in itertools::iproduct!(self.cells(), 0..3) {
+            let (b, c) = ((axis + 1) % 3, (axis + 2) % 3);
+            if cell[b] < 0 || cell[c] < 0 || !self.is_crossed((cell, axis)) {
+                continue;
+            }
+            // Counterclockwise around the edge, seen from the end of the edge, such that the normal points along the edge if it leaves the inside.
+            let mut quad = [cell, offset(cell, b, -1), offset(offset(cell, b, -1), c, -1), offset(cell, c, -1)].map(|around| vertices[&around]);
+            if self.value(cell) >= 0. {
+                quad.reverse();
+            }
+            let diagonal = |i: usize, j: usize| (positions[quad[i]] - positions[quad[j]]).norm();
+            if diagonal(0, 2) <= diagonal(1, 3) {
+                faces.extend([vec![quad[0], quad[1], quad[2]], vec![quad[0], quad[2], quad[3]]]);
+            } else {
+                faces.extend([vec![quad[1], quad[2], quad[3]], vec![quad[1], quad[3], quad[0]]]);
+            }
+        }
+        Mesh::from(&faces, &positions).map(|(mesh, _, _)| mesh)
+    }
+}
+
+fn offset(mut sample: [i64; 3], axis: usize, amount: i64) -> [i64; 3] {
+    sample[axis] += amount;
+    sample
+}
+
+// The grid edge between two neighboring samples.
+fn grid_edge(a: [i64; 3], b: [i64; 3]) -> GridEdge {
+    let axis = (0..3).find(|&axis| a[axis] != b[axis]).unwrap();
+    (if a[axis] < b[axis] { a } else { b }, axis)
+}
@@ -2,53 +2,337 @@ use crate::prelude::*;
 use bvh::{
     aabb::{Aabb, Bounded},
     bounding_hierarchy::BHShape,
-    bvh::Bvh,
+    bvh::{Bvh, BvhNode},
     point_query::PointDistance,
+    ray::Ray,
 };
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct FaceLocation<M: Tag>((Bvh<f64, 3>, Vec<TriangleBvhShape<M>>));
+pub struct FaceLocation<M: Tag> {
+    bvh: Bvh<f64, 3>,
+    triangles: Vec<TriangleBvhShape<M>>,
+    // Per node of the hierarchy, the moments of its triangles (for far-field approximations).
+    moments: Vec<Moment>,
+}
+
+// Area, area-weighted centroid, vector area, first moment of the vector area (sum of (x - centroid) n^T dA) and radius around the centroid of a set of triangles.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+struct Moment {
+    area: Float,
+    centroid: Vector3D,
+    vector_area: Vector3D,
+    tensor: nalgebra::Matrix3<Float>,
+    radius: Float,
+}
+// A triangle of the hierarchy as (positions of its corners, its corners in the face, node index, face).
+pub(crate) type TrianglePart<M> = ([Vector3D; 3], [usize; 3], usize, FaceKey<M>);
+
+// Intersection of a ray with a face, at `origin + t * direction`.
+// The hit point lies in the triangle of the face with corners `corners` (indices into `vertices(face)`), with barycentric coordinates `barycentric`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hit<M: Tag> {
+    pub face: FaceKey<M>,
+    pub t: Float,
+    pub corners: [usize; 3],
+    pub barycentric: Vector3D,
+}
+
+// Point on the surface, in the triangle of face `face` with corners `corners` (indices into `vertices(face)`), with barycentric coordinates `barycentric`.
+// The distance is to the point that was queried.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SurfacePoint<M: Tag> {
+    pub face: FaceKey<M>,
+    pub corners: [usize; 3],
+    pub barycentric: Vector3D,
+    pub position: Vector3D,
+    pub distance: Float,
+}
+
 impl<M: Tag> FaceLocation<M> {
     #[must_use]
     pub fn nearest(&self, point: &[f64; 3]) -> FaceKey<M> {
-        let neighbor = self.0.0.nearest_to(nalgebra::Point3::from_slice(point), &self.0.1);
+        let neighbor = self.bvh.nearest_to(nalgebra::Point3::from_slice(point), &self.triangles);
         let (t, _) = neighbor.unwrap();
         t.real_index
     }
-    fn overwrite(&mut self, shapes: &mut [TriangleBvhShape<M>]) {
-        self.0.0 = Bvh::build(shapes);
-        self.0.1 = shapes.to_vec();
+
+    // Closest point on the surface to the given point. None if there are no faces.
+    #[must_use]
+    pub fn closest_point(&self, point: Vector3D) -> Option<SurfacePoint<M>> {
+        let (triangle, distance) = self.bvh.nearest_to(point.into(), &self.triangles)?;
+        let (position, barycentric) = triangle.closest_point(point);
+        Some(SurfacePoint {
+            face: triangle.real_index,
+            corners: triangle.face_corners,
+            barycentric,
+            position,
+            distance,
+        })
+    }
+
+    // Closest points on the surface to all given points (see `FaceLocation::closest_point`).
+    #[must_use]
+    pub fn closest_points(&self, points: &[Vector3D]) -> Vec<Option<SurfacePoint<M>>> {
+        points.iter().map(|&point| self.closest_point(point)).collect()
+    }
+
+    // First intersection of the ray `origin + t * direction` (t >= 0) with the faces (from either side).
+    #[must_use]
+    pub fn ray_intersect(&self, origin: Vector3D, direction: Vector3D) -> Option<Hit<M>> {
+        self.ray_hits(origin, direction).min_by(|a, b| a.t.total_cmp(&b.t))
+    }
+
+    // All intersections of the ray `origin + t * direction` (t >= 0) with the faces, sorted by t. Every face is hit at most once.
+    #[must_use]
+    pub fn ray_intersect_all(&self, origin: Vector3D, direction: Vector3D) -> Vec<Hit<M>> {
+        let mut hits = self.ray_hits(origin, direction).collect_vec();
+        hits.sort_by(|a, b| a.t.total_cmp(&b.t));
+        hits.into_iter().unique_by(|hit| hit.face).collect()
+    }
+
+    // Whether the ray `origin + t * direction` hits any face with 0 < t < `t_max` (for example, the segment from a point to a light).
+    // To test visibility from a point on the surface, offset the origin slightly (otherwise, its own face is hit).
+    #[must_use]
+    pub fn occluded(&self, origin: Vector3D, direction: Vector3D, t_max: Float) -> bool {
+        self.ray_hits(origin, direction).any(|hit| hit.t > 0. && hit.t < t_max)
+    }
+
+    // Generalized winding number of the surface around the given point (Jacobson et al., 2013): 1 inside and 0 outside a closed surface,
+    // varying smoothly in between for open or self-intersecting surfaces. Distant clusters of triangles are approximated by dipoles (Barill et al., 2018).
+    #[must_use]
+    pub fn winding_number(&self, point: Vector3D) -> Float {
+        self.winding_number_with_accuracy(point, 3.)
+    }
+
+    // Same as `FaceLocation::winding_number`, where clusters are approximated only if the point is more than `accuracy` times their radius away.
+    // Larger values are more accurate (and slower), and `INF` gives the exact winding number.
+    #[must_use]
+    pub fn winding_number_with_accuracy(&self, point: Vector3D, accuracy: Float) -> Float {
+        if self.bvh.nodes.is_empty() {
+            return 0.;
+        }
+        let mut stack = vec![0];
+        let mut solid_angle = 0.;
+        while let Some(node_index) = stack.pop() {
+            let moment = self.moments[node_index];
+            let d = moment.centroid - point;
+            let distance = d.norm();
+            if distance > accuracy * moment.radius {
+                // Taylor expansion (up to second order) of the dipole field d / |d|^3 around the centroid.
+                let jacobian = nalgebra::Matrix3::identity() / distance.powi(3) - d * d.transpose() * (3. / distance.powi(5));
+                solid_angle += d.dot(&moment.vector_area) / distance.powi(3) + (jacobian * moment.tensor).trace();
+                continue;
+            }
+            match self.bvh.nodes[node_index] {
+                BvhNode::Leaf { shape_index, .. } => {
+                    let [a, b, c] = self.triangles[shape_index].corners.map(|corner| corner - point);
+                    let (la, lb, lc) = (a.norm(), b.norm(), c.norm());
+                    let denominator = la * lb * lc + a.dot(&b) * lc + b.dot(&c) * la + c.dot(&a) * lb;
+                    solid_angle += 2. * a.dot(&b.cross(&c)).atan2(denominator);
+                }
+                BvhNode::Node {
+                    child_l_index, child_r_index, ..
+                } => stack.extend([child_l_index, child_r_index]),
+            }
+        }
+        solid_angle / (4. * PI)
+    }
+
+    // Whether the given point lies inside the surface (winding number above one half).
+    #[must_use]
+    pub fn contains(&self, point: Vector3D) -> bool {
+        self.winding_number(point) > 0.5
+    }
+
+    // Distance to the surface, negative inside (see `FaceLocation::contains`). Infinite if there are no faces.
+    #[must_use]
+    pub fn signed_distance(&self, point: Vector3D) -> Float {
+        let distance = self.closest_point(point).map_or(INF, |closest| closest.distance);
+        if self.contains(point) { -distance } else { distance }
+    }
+
+    // Signed distance field, sampled on a grid spanning the box `bbox` = (min, max) with `resolution` samples along each axis (see `Grid::from_fn`).
+    #[must_use]
+    pub fn sdf_grid(&self, bbox: (Vector3D, Vector3D), resolution: [usize; 3]) -> Grid<Float> {
+        Grid::from_fn(bbox, resolution, |point| self.signed_distance(point))
+    }
+
+    fn ray_hits(&self, origin: Vector3D, direction: Vector3D) -> impl Iterator<Item = Hit<M>> + '_ {
+        let candidates = if direction == Vector3D::zeros() {
+            vec![]
+        } else {
+            self.bvh.traverse(&Ray::new(origin.into(), direction), &self.triangles)
+        };
+        candidates.into_iter().filter_map(move |triangle| {
+            let [a, b, c] = triangle.corners;
+            geom::ray_triangle_intersection(origin, direction, (a, b, c)).map(|(t, barycentric)| Hit {
+                face: triangle.real_index,
+                t,
+                corners: triangle.face_corners,
+                barycentric,
+            })
+        })
+    }
+
+    // Number of triangles in the hierarchy.
+    pub(crate) fn nr_triangles(&self) -> usize {
+        self.triangles.len()
+    }
+
+    // Triangle with the given index, as (positions of its corners, its corners in the face, face).
+    pub(crate) fn triangle(&self, index: usize) -> ([Vector3D; 3], [usize; 3], FaceKey<M>) {
+        let triangle = &self.triangles[index];
+        (triangle.corners, triangle.face_corners, triangle.real_index)
+    }
+
+    // Indices of the triangles whose bounding box overlaps the bounding box of the given triangle.
+    pub(crate) fn overlapping(&self, corners: [Vector3D; 3]) -> Vec<usize> {
+        let query = triangle_aabb(corners);
+        let mut stack = if self.bvh.nodes.is_empty() { vec![] } else { vec![0] };
+        let mut indices = vec![];
+        while let Some(node_index) = stack.pop() {
+            match self.bvh.nodes[node_index] {
+                BvhNode::Leaf { shape_index, .. } => {
+                    if triangle_aabb(self.triangles[shape_index].corners).intersects_aabb(&query) {
+                        indices.push(shape_index);
+                    }
+                }
+                BvhNode::Node {
+                    child_l_index,
+                    child_l_aabb,
+                    child_r_index,
+                    child_r_aabb,
+                    ..
+                } => {
+                    if child_l_aabb.intersects_aabb(&query) {
+                        stack.push(child_l_index);
+                    }
+                    if child_r_aabb.intersects_aabb(&query) {
+                        stack.push(child_r_index);
+                    }
+                }
+            }
+        }
+        indices
+    }
+
+    // The nodes of the hierarchy, and its triangles.
+    pub(crate) fn parts(&self) -> (&[BvhNode<f64, 3>], Vec<TrianglePart<M>>) {
+        let triangles = self
+            .triangles
+            .iter()
+            .map(|t| (t.corners, t.face_corners, t.node_index, t.real_index))
+            .collect_vec();
+        (&self.bvh.nodes, triangles)
+    }
+
+    // Inverse of `parts`, without rebuilding the hierarchy.
+    pub(crate) fn from_parts(nodes: Vec<BvhNode<f64, 3>>, triangles: Vec<TrianglePart<M>>) -> Self {
+        let shapes = triangles
+            .into_iter()
+            .map(|(corners, face_corners, node_index, real_index)| TriangleBvhShape {
+                corners,
+                face_corners,
+                node_index,
+                real_index,
+            })
+            .collect_vec();
+        Self::new(Bvh { nodes }, shapes)
+    }
+
+    fn new(bvh: Bvh<f64, 3>, triangles: Vec<TriangleBvhShape<M>>) -> Self {
+        let mut face_location = Self {
+            bvh,
+            triangles,
+            moments: vec![],
+        };
+        face_location.moments = vec![Moment::default(); face_location.bvh.nodes.len()];
+        if !face_location.bvh.nodes.is_empty() {
+            face_location.compute_moments(0);
+        }
+        face_location
+    }
+
+    // Moments of the given node and its descendants.
+    fn compute_moments(&mut self, node_index: usize) -> Moment {
+        let moment = match self.bvh.nodes[node_index] {
+            BvhNode::Leaf { shape_index, .. } => {
+                let [a, b, c] = self.triangles[shape_index].corners;
+                let centroid = (a + b + c) / 3.;
+                let vector_area = (b - a).cross(&(c - a)) / 2.;
+                Moment {
+                    area: vector_area.norm(),
+                    centroid,
+                    vector_area,
+                    tensor: nalgebra::Matrix3::zeros(),
+                    radius: [a, b, c].iter().map(|p| (p - centroid).norm()).fold(0., Float::max),
+                }
+            }
+            BvhNode::Node {
+                child_l_index, child_r_index, ..
+            } => {
+                let children = [self.compute_moments(child_l_index), self.compute_moments(child_r_index)];
+                let area = children[0].area + children[1].area;
+                let centroid = if area > 0. {
+                    (children[0].centroid * children[0].area + children[1].centroid * children[1].area) / area
+                } else {
+                    (children[0].centroid + children[1].centroid) / 2.
+                };
+                Moment {
+                    area,
+                    centroid,
+                    vector_area: children[0].vector_area + children[1].vector_area,
+                    tensor: children
+                        .iter()
+                        .map(|child| child.tensor + (child.centroid - centroid) * child.vector_area.transpose())
+                        .sum(),
+                    radius: children
+                        .iter()
+                        .map(|child| (child.centroid - centroid).norm() + child.radius)
+                        .fold(0., Float::max),
+                }
+            }
+        };
+        self.moments[node_index] = moment;
+        moment
     }
 }
 impl<M: Tag> Default for FaceLocation<M> {
     fn default() -> Self {
-        Self((Bvh::build::<TriangleBvhShape<M>>(&mut []), Vec::new()))
+        Self::new(Bvh::build::<TriangleBvhShape<M>>(&mut []), Vec::new())
     }
 }
 
 impl<M: Tag> Mesh<M> {
+    // Bounding volume hierarchy of the faces. Polygonal faces are triangulated (see `Mesh::face_triangles`), or split into a fan if that fails.
     #[must_use]
     pub fn bvh(&self) -> FaceLocation<M> {
-        let mut bvh = FaceLocation::default();
         let mut triangles = self
             .face_ids()
-            .iter()
-            .enumerate()
-            .map(|(i, &face_id)| TriangleBvhShape {
-                corners: [
-                    self.position(self.vertices(face_id)[0]),
-                    self.position(self.vertices(face_id)[1]),
-                    self.position(self.vertices(face_id)[2]),
-                ],
-                node_index: i,
-                real_index: face_id,
+            .into_iter()
+            .flat_map(|face_id| {
+                let corners = self.vertices(face_id);
+                let face_triangles = self
+                    .face_triangles(face_id)
+                    .unwrap_or_else(|_| (1..corners.len() - 1).map(|i| [0, i, i + 1]).collect());
+                face_triangles.into_iter().map(move |face_corners| TriangleBvhShape {
+                    corners: face_corners.map(|i| self.position(corners[i])),
+                    face_corners,
+                    node_index: 0,
+                    real_index: face_id,
+                })
             })
             .collect_vec();
 
-        bvh.overwrite(&mut triangles);
-        bvh
+        FaceLocation::new(Bvh::build(&mut triangles), triangles)
+    }
+
+    // Signed distance field of the mesh (see `FaceLocation::sdf_grid`).
+    #[must_use]
+    pub fn sdf_grid(&self, bbox: (Vector3D, Vector3D), resolution: [usize; 3]) -> Grid<Float> {
+        self.bvh().sdf_grid(bbox, resolution)
     }
 }
 
@@ -57,33 +341,43 @@ impl<M: Tag> Mesh<M> {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct TriangleBvhShape<M: Tag> {
     corners: [Vector3D; 3],
+    face_corners: [usize; 3],
     node_index: usize,
     real_index: FaceKey<M>,
 }
 
+impl<M: Tag> TriangleBvhShape<M> {
+    fn closest_point(&self, point: Vector3D) -> (Vector3D, Vector3D) {
+        geom::closest_point_on_triangle(point, (self.corners[0], self.corners[1], self.corners[2]))
+    }
+}
+
 impl<M: Tag> PointDistance<f64, 3> for TriangleBvhShape<M> {
     fn distance_squared(&self, query_point: nalgebra::Point<f64, 3>) -> f64 {
-        geom::distance_to_triangle(
-            Vector3D::new(query_point[0], query_point[1], query_point[2]),
-            (self.corners[0], self.corners[1], self.corners[2]),
-        )
+        (self.closest_point(query_point.coords).0 - query_point.coords).norm_squared()
     }
 }
 
 impl<M: Tag> Bounded<f64, 3> for TriangleBvhShape<M> {
     fn aabb(&self) -> Aabb<f64, 3> {
-        let min_x = self.corners.iter().map(|v| v.x).fold(f64::MAX, f64::min);
-        let min_y = self.corners.iter().map(|v| v.y).fold(f64::MAX, f64::min);
-        let min_z = self.corners.iter().map(|v| v.z).fold(f64::MAX, f64::min);
-        let max_x = self.corners.iter().map(|v| v.x).fold(f64::MIN, f64::max);
-        let max_y = self.corners.iter().map(|v| v.y).fold(f64::MIN, f64::max);
-        let max_z = self.corners.iter().map(|v| v.z).fold(f64::MIN, f64::max);
-        let min = nalgebra::Point3::new(min_x, min_y, min_z);
-        let max = nalgebra::Point3::new(max_x, max_y, max_z);
-        Aabb::with_bounds(min, max)
+        triangle_aabb(self.corners)
     }
 }
 
+// Padded, such that flat (axis-aligned) triangles do not get an empty box, which rays could slip past.
+fn triangle_aabb(corners: [Vector3D; 3]) -> Aabb<f64, 3> {
+    let min_x = corners.iter().map(|v| v.x).fold(f64::MAX, f64::min);
+    let min_y = corners.iter().map(|v| v.y).fold(f64::MAX, f64::min);
+    let min_z = corners.iter().map(|v| v.z).fold(f64::MAX, f64::min);
+    let max_x = corners.iter().map(|v| v.x).fold(f64::MIN, f64::max);
+    let max_y = corners.iter().map(|v| v.y).fold(f64::MIN, f64::max);
+    let max_z = corners.iter().map(|v| v.z).fold(f64::MIN, f64::max);
+    let padding = geom::tolerance(geom::scale(&corners));
+    let min = nalgebra::Point3::new(min_x, min_y, min_z) - Vector3D::repeat(padding);
+    let max = nalgebra::Point3::new(max_x, max_y, max_z) + Vector3D::repeat(padding);
+    Aabb::with_bounds(min, max)
+}
+
 impl<M: Tag> BHShape<f64, 3> for TriangleBvhShape<M> {
     fn set_bh_node_index(&mut self, index: usize) {
         self.node_index = index;
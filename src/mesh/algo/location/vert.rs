@@ -1,34 +1,116 @@
 use crate::prelude::*;
 use kdtree::{KdTree, distance::squared_euclidean};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 
+// Spatial index of the vertices (kd-tree), which can be kept up to date while the mesh is edited.
+// All queries return squared Euclidean distances (as `VertLocation::nearest` always has), and results are sorted by distance.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct VertLocation<M: Tag>(KdTree<f64, VertKey<M>, [f64; 3]>);
+pub struct VertLocation<M: Tag> {
+    tree: KdTree<f64, (VertKey<M>, u64), [f64; 3]>,
+    // The indexed vertices, with their position and version. Entries in the tree with an older version are stale (removed or moved).
+    verts: HashMap<VertKey<M>, ([f64; 3], u64)>,
+    version: u64,
+}
 
 impl<M: Tag> VertLocation<M> {
+    // Closest vertex to the given point. None if there are no vertices.
+    #[must_use]
+    pub fn nearest(&self, point: &[f64; 3]) -> Option<(f64, VertKey<M>)> {
+        self.iter_nearest(point).next()
+    }
+
+    // The `k` closest vertices to the given point.
+    #[must_use]
+    pub fn k_nearest(&self, point: &[f64; 3], k: usize) -> Vec<(f64, VertKey<M>)> {
+        self.iter_nearest(point).take(k).collect()
+    }
+
+    // All vertices within (or at) squared distance `radius_squared` of the given point.
+    #[must_use]
+    pub fn within_radius(&self, point: &[f64; 3], radius_squared: f64) -> Vec<(f64, VertKey<M>)> {
+        self.iter_nearest(point).take_while(|&(d, _)| d <= radius_squared).collect()
+    }
+
+    // Closest vertex to the given point that is not in `excluded` (for example, the vertex itself, or vertices that are already matched).
+    #[must_use]
+    pub fn nearest_excluding(&self, point: &[f64; 3], excluded: &HashSet<VertKey<M>>) -> Option<(f64, VertKey<M>)> {
+        self.iter_nearest(point).find(|(_, vert_id)| !excluded.contains(vert_id))
+    }
+
+    // Add a vertex at the given position, or move it if it is already indexed. Returns whether it is indexed:
+    // positions with non-finite coordinates are not (and a vertex moved to such a position is removed).
+    pub fn insert(&mut self, vert_id: VertKey<M>, point: [f64; 3]) -> bool {
+        self.version += 1;
+        let indexed = self.tree.add(point, (vert_id, self.version)).is_ok();
+        if indexed {
+            self.verts.insert(vert_id, (point, self.version));
+        } else {
+            self.verts.remove(&vert_id);
+        }
+        self.compact();
+        indexed
+    }
+
+    // Remove a vertex. Returns whether it was indexed.
+    pub fn remove(&mut self, vert_id: VertKey<M>) -> bool {
+        let removed = self.verts.remove(&vert_id).is_some();
+        self.compact();
+        removed
+    }
+
+    #[must_use]
+    pub fn contains(&self, vert_id: VertKey<M>) -> bool {
+        self.verts.contains_key(&vert_id)
+    }
+
     #[must_use]
-    pub fn nearest(&self, point: &[f64; 3]) -> (f64, VertKey<M>) {
-        let neighbors = self.0.nearest(point, 1, &squared_euclidean).unwrap();
-        let (d, i) = neighbors.first().unwrap();
-        (*d, **i)
+    pub fn len(&self) -> usize {
+        self.verts.len()
     }
 
-    fn add(&mut self, point: [f64; 3], index: VertKey<M>) {
-        self.0.add(point, index).unwrap();
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.verts.is_empty()
+    }
+
+    // Vertices by increasing squared distance, skipping stale entries. Empty for non-finite points.
+    fn iter_nearest<'a>(&'a self, point: &'a [f64; 3]) -> impl Iterator<Item = (f64, VertKey<M>)> + 'a {
+        self.tree
+            .iter_nearest(point, &squared_euclidean)
+            .into_iter()
+            .flatten()
+            .filter(|(_, (vert_id, version))| self.verts.get(vert_id).is_some_and(|&(_, current)| current == *version))
+            .map(|(d, &(vert_id, _))| (d, vert_id))
+    }
+
+    // Rebuild the tree once most of its entries are stale.
+    fn compact(&mut self) {
+        if self.tree.size() > 2 * self.verts.len() + 64 {
+            let mut tree = KdTree::new(3);
+            // The indexed positions are finite, so they are all added again.
+            self.verts.retain(|&vert_id, &mut (point, version)| tree.add(point, (vert_id, version)).is_ok());
+            self.tree = tree;
+        }
     }
 }
 impl<M: Tag> Default for VertLocation<M> {
     fn default() -> Self {
-        Self(KdTree::new(3))
+        Self {
+            tree: KdTree::new(3),
+            verts: HashMap::new(),
+            version: 0,
+        }
     }
 }
 
 impl<M: Tag> Mesh<M> {
+    // Index of the vertices (see `VertLocation`), without the vertices with non-finite positions.
     #[must_use]
     pub fn kdtree(&self) -> VertLocation<M> {
         let mut tree = VertLocation::default();
         for id in self.vert_ids() {
-            tree.add(self.position(id).into(), id);
+            tree.insert(id, self.position(id).into());
         }
         tree
     }
@@ -0,0 +1,104 @@
+use crate::prelude::*;
+use sparse::{Csr, Triplets};
+
+// Discretization of the mass matrix (the inner product of piecewise-linear functions).
+//      Lumped: diagonal, a third of the area of every triangle is assigned to each of its corners (barycentric areas)
+//      Voronoi: diagonal, with the mixed Voronoi areas of the vertices (see `Mesh::mixed_areas`)
+//      Full: the (consistent) Galerkin mass matrix, with off-diagonal entries between neighboring vertices
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MassMatrix {
+    #[default]
+    Lumped,
+    Voronoi,
+    Full,
+}
+
+// Sparse differential operators on triangle meshes, for piecewise-linear functions on the vertices and piecewise-constant vector fields on the faces.
+// Vertices and faces are indexed as in `Mesh::vert_index_map` and `Mesh::face_index_map`.
+// All operators return an error if the mesh has a face that is not a triangle.
+impl<M: Tag> Mesh<M> {
+    // Cotangent Laplacian (|V| x |V|), symmetric and positive semi-definite: L_ij = -(cot a + cot b) / 2 for neighbors i and j, L_ii = -sum_j L_ij.
+    // It is the weak (integrated) form, so solve L u = M f for the Poisson equation -Δu = f (with M a mass matrix).
+    pub fn cotan_laplacian(&self) -> Result<Csr, MeshError<M>> {
+        self.is_triangular()?;
+        let vert_map = self.vert_index_map();
+        let index = |vert_id| *vert_map.id(&vert_id).unwrap();
+
+        let mut triplets = Triplets::new(self.nr_verts(), self.nr_verts());
+        for edge_id in self.edge_ids() {
+            // Every half-edge contributes the cotangent of its own opposite angle.
+            let (i, j) = (index(self.root(edge_id)), index(self.toor(edge_id)));
+            let weight = self.cotan(edge_id) / 2.;
+            triplets.push(i, j, -weight);
+            triplets.push(j, i, -weight);
+            triplets.push(i, i, weight);
+            triplets.push(j, j, weight);
+        }
+        Ok(triplets.to_csr())
+    }
+
+    // Mass matrix (|V| x |V|), with the given discretization.
+    pub fn mass_matrix(&self, kind: MassMatrix) -> Result<Csr, MeshError<M>> {
+        self.is_triangular()?;
+        let vert_map = self.vert_index_map();
+        let index = |vert_id| *vert_map.id(&vert_id).unwrap();
+
+        match kind {
+            MassMatrix::Lumped => {
+                let mut areas = vec![0.; self.nr_verts()];
+                for face_id in self.face_ids() {
+                    for vert_id in self.vertices(face_id) {
+                        areas[index(vert_id)] += self.size(face_id) / 3.;
+                    }
+                }
+                Ok(Csr::diagonal(&areas))
+            }
+            MassMatrix::Voronoi => {
+                let areas = self.vert_ids().into_iter().map(|vert_id| self.mixed_area(vert_id)).collect::<Vec<_>>();
+                Ok(Csr::diagonal(&areas))
+            }
+            MassMatrix::Full => {
+                let mut triplets = Triplets::new(self.nr_verts(), self.nr_verts());
+                for face_id in self.face_ids() {
+                    let area = self.size(face_id);
+                    let corners = self.vertices(face_id).into_iter().map(index).collect::<Vec<_>>();
+                    for &i in &corners {
+                        for &j in &corners {
+                            triplets.push(i, j, if i == j { area / 6. } else { area / 12. });
+                        }
+                    }
+                }
+                Ok(triplets.to_csr())
+            }
+        }
+    }
+
+    // Gradient (3|F| x |V|): rows 3f, 3f + 1 and 3f + 2 are the x, y and z components of the (constant) gradient in face f.
+    pub fn gradient(&self) -> Result<Csr, MeshError<M>> {
+        self.is_triangular()?;
+        let vert_map = self.vert_index_map();
+
+        let mut triplets = Triplets::new(3 * self.nr_faces(), self.nr_verts());
+        for (f, face_id) in self.face_ids().into_iter().enumerate() {
+            // The gradient of the hat function of a corner is the opposite edge, rotated inwards and scaled by 1 / (2 * area).
+            let (normal, area) = (self.normal(face_id), self.size(face_id));
+            for edge_id in self.edges(face_id) {
+                let opposite = self.next(self.next(edge_id));
+                let gradient = normal.cross(&self.vector(edge_id)) / (2. * area);
+                let i = *vert_map.id(&self.root(opposite)).unwrap();
+                for axis in 0..3 {
+                    triplets.push(3 * f + axis, i, gradient[axis]);
+                }
+            }
+        }
+        Ok(triplets.to_csr())
+    }
+
+    // Divergence (|V| x 3|F|) of a face vector field (laid out as for `Mesh::gradient`), integrated over the vertex areas.
+    // It is the negative adjoint of the gradient (D = -G^T A, with A the face areas), such that D G = -L.
+    pub fn divergence(&self) -> Result<Csr, MeshError<M>> {
+        let gradient = self.gradient()?;
+        let areas = self.face_ids().into_iter().flat_map(|face_id| [-self.size(face_id); 3]).collect::<Vec<_>>();
+        Ok(gradient.transpose().mul(&Csr::diagonal(&areas)))
+    }
+}
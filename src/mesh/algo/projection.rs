@@ -1,12 +1,15 @@
 use crate::prelude::*;
 
 impl<M: Tag> Mesh<M> {
-    // Project a face to 2d
+    // Project a face to 2d, onto its best-fit plane (with the first corner at the origin, and the first edge along the x-axis).
+    // The corners of the projected face are in counterclockwise order.
     pub fn project(&self, face: FaceKey<M>) -> Vec<Vector2D> {
-        // TODO: Find a better plane
-        let normal = self.normal(face);
+        let (_, normal) = self.best_fit_plane(face);
         let edge = self.vector(*self.edges(face).first().unwrap());
-        let plane = (edge.normalize(), edge.cross(&normal).normalize());
+        let u = (edge - normal * edge.dot(&normal))
+            .try_normalize(EPS)
+            .unwrap_or_else(|| normal.cross(&Vector3D::repeat(1.)).normalize());
+        let plane = (u, normal.cross(&u));
         let reference = self.position(self.vertices(face)[0]);
 
         self.vertices(face)
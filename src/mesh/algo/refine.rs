@@ -230,54 +230,40 @@ impl<M: Tag> Mesh<M> {
         // Draw circle with radius a_c1_distance and center a_position
         // Draw circle with radius b_c1_distance and center b_position
         // Find intersection point with negative y: this is the position of c1
-        let R = a_c1_distance;
-        let r = b_c1_distance;
-        let d = a_b_distance;
-
-        let x = (d * d - r * r + R * R) / (2. * d);
-        let yy = R * R - x * x;
-        let y = if yy < 0. { 0. } else { -(yy.sqrt()) };
-
-        let c1_position = Vector2D::new(x, y);
-        assert!(c1_position[1] <= 0., "c1_position: {:?}", c1_position);
+        let c1_position = Self::circle_intersection(a_b_distance, a_c1_distance, b_c1_distance, -1.);
+        assert!(c1_position[1] <= 0., "c1_position: {c1_position:?}");
 
         // Calculate the position of c2
         // Draw circle with radius a_c2_distance and center a_position
         // Draw circle with radius b_c2_distance and center b_position
         // Find intersection point with positive y: this is the position of c2
-        let R = a_c2_distance;
-        let r = b_c2_distance;
-        let d = a_b_distance;
-
-        let x = (d * d - r * r + R * R) / (2. * d);
-        let yy = R * R - x * x;
-        let y = if yy < 0. { 0. } else { yy.sqrt() };
-        let c2_position = Vector2D::new(x, y);
-        assert!(c2_position[1] >= 0., "c2_position: {:?}", c2_position);
-
-        // println!("a_position: {a_position:?}");
-        // println!("b_position: {b_position:?}");
-        // println!("c1_position: {c1_position:?}");
-        // println!("c2_position: {c2_position:?}");
+        let c2_position = Self::circle_intersection(a_b_distance, a_c2_distance, b_c2_distance, 1.);
+        assert!(c2_position[1] >= 0., "c2_position: {c2_position:?}");
 
         // Find intersection of a_b and c1_c2
-        // Calculate the intersection of the lines a_b and c1_c2
-
-        let intersection_maybe = geom::calculate_2d_lineseg_intersection(a_position, b_position, c1_position, c2_position);
-
-        if intersection_maybe.is_none() {
+        //      if a (or b) lies on the line c1_c2, the edge is split at a (or b)
+        //      if a and b lie on the same side of the line c1_c2, the edge is split at the endpoint closest to the line
+        let side_a = geom::orient2d(c1_position, c2_position, a_position);
+        let side_b = geom::orient2d(c1_position, c2_position, b_position);
+        if side_a == 0. && side_b == 0. {
             return None;
         }
+        if side_a == 0. {
+            return Some(a);
+        }
+        if side_b == 0. {
+            return Some(b);
+        }
+        if side_a * side_b > 0. {
+            return if side_a.abs() < side_b.abs() { Some(a) } else { Some(b) };
+        }
 
-        let intersection = intersection_maybe.unwrap();
-
-        // assert!(intersection[1].abs() == 0., "{intersection:?}");
+        let (intersection, _) = geom::calculate_2d_lineseg_intersection(a_position, b_position, c1_position, c2_position)?;
 
         // The portion of the edge a_b that is before the intersection
-        let t = intersection.0[0] / a_b_distance;
-
-        // println!("t: {}", t);
+        let t = intersection.x / a_b_distance;
 
+        // Splitting (very) close to an endpoint would create degenerate faces
         if t < 0.001 {
             return Some(a);
         }
@@ -299,7 +285,16 @@ impl<M: Tag> Mesh<M> {
         // Move the split vertex to the correct position
         self.set_position(split_vertex, split_position);
 
-        return Some(split_vertex);
+        Some(split_vertex)
+    }
+
+    // Intersection of the circles around (0, 0) with radius `radius_a` and around (d, 0) with radius `radius_b`, on the side of `sign`.
+    // If the circles do not intersect (which can happen due to rounding), the point on the x-axis is returned.
+    fn circle_intersection(d: Float, radius_a: Float, radius_b: Float, sign: Float) -> Vector2D {
+        let x = (d * d - radius_b * radius_b + radius_a * radius_a) / (2. * d);
+        let yy = radius_a * radius_a - x * x;
+        let y = if yy < 0. { 0. } else { sign * yy.sqrt() };
+        Vector2D::new(x, y)
     }
 
     // pub fn refine(&mut self, n: usize) {
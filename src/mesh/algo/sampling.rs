@@ -0,0 +1,224 @@
+use crate::prelude::*;
+use itertools::Itertools;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+// Point sampled on the surface (with distance zero), with its normal interpolated from the vertex normals of its triangle.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sample<M: Tag> {
+    pub point: SurfacePoint<M>,
+    pub normal: Vector3D,
+}
+
+// Distance between samples.
+//      Euclidean: straight-line distance.
+//      Geodesic: distance over the surface. For Poisson-disk sampling, it is approximated from the straight-line distance and the angle between the normals,
+//          assuming constant curvature between the samples (Bowers et al., 2010). For farthest-point sampling, it is the shortest path along the edges.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Metric {
+    #[default]
+    Euclidean,
+    Geodesic,
+}
+
+// Random samples on the surface. Polygonal faces are sampled by their triangles (see `Mesh::bvh`), and the same seed gives the same samples.
+impl<M: Tag> Mesh<M> {
+    // `n` independent samples, uniformly distributed over the area of the surface.
+    #[must_use]
+    pub fn sample_uniform(&self, n: usize, seed: u64) -> Vec<Sample<M>> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let sampler = Sampler::new(self);
+        (0..n).filter_map(|_| sampler.sample(rng.random(), &mut rng)).collect()
+    }
+
+    // `n` samples, uniformly distributed over the area of the surface, but stratified: the surface is divided into `n` parts of equal area
+    // (in the order of the triangles), with one sample in each part. This spreads the samples more evenly than `Mesh::sample_uniform`.
+    #[must_use]
+    pub fn sample_stratified(&self, n: usize, seed: u64) -> Vec<Sample<M>> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let sampler = Sampler::new(self);
+        (0..n)
+            .filter_map(|i| sampler.sample((i as Float + rng.random::<Float>()) / n as Float, &mut rng))
+            .collect()
+    }
+
+    // Samples such that no two samples are closer than `radius` (in the given metric), by dart throwing: uniform candidates (enough to cover
+    // the surface many times) are accepted in random order if they are not too close to an accepted sample.
+    #[must_use]
+    pub fn sample_poisson_disk(&self, radius: Float, metric: Metric, seed: u64) -> Vec<Sample<M>> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let sampler = Sampler::new(self);
+        if radius <= 0. || sampler.area <= 0. {
+            return vec![];
+        }
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let nr_candidates = (16. * sampler.area / (radius * radius)).ceil() as usize;
+
+        // Accepted samples, in a grid of cells of the size of the radius. Geodesic distances are at least the straight-line distances,
+        // so conflicting samples are always in neighboring cells.
+        let mut samples: Vec<Sample<M>> = vec![];
+        let mut grid: HashMap<[i64; 3], Vec<usize>> = HashMap::new();
+        #[allow(clippy::cast_possible_truncation)]
+        let cell = |p: Vector3D| [0, 1, 2].map(|axis| (p[axis] / radius).floor() as i64);
+        for _ in 0..nr_candidates {
+            let Some(candidate) = sampler.sample(rng.random(), &mut rng) else {
+                continue;
+            };
+            let c = cell(candidate.point.position);
+            let conflict = (0..27)
+                .map(|i| [c[0] + i % 3 - 1, c[1] + i / 3 % 3 - 1, c[2] + i / 9 - 1])
+                .filter_map(|neighbor| grid.get(&neighbor))
+                .flatten()
+                .any(|&index| distance(&samples[index], &candidate, metric) < radius);
+            if !conflict {
+                grid.entry(c).or_default().push(samples.len());
+                samples.push(candidate);
+            }
+        }
+        samples
+    }
+
+    // `n` vertices (or all vertices, if there are fewer) that are spread out: starting from a random vertex, every next vertex is the vertex
+    // farthest from the vertices so far (in the given metric). Returned as samples at the vertices, with the vertex normals.
+    #[must_use]
+    pub fn farthest_point_samples(&self, n: usize, metric: Metric, seed: u64) -> Vec<(VertKey<M>, Sample<M>)> {
+        let vert_ids = self.vert_ids();
+        if vert_ids.is_empty() {
+            return vec![];
+        }
+        let index_of: HashMap<_, _> = vert_ids.iter().enumerate().map(|(i, &vert_id)| (vert_id, i)).collect();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut nearest = vec![INF; vert_ids.len()];
+        let mut next = rng.random_range(0..vert_ids.len());
+        let mut selected = vec![];
+        while selected.len() < n.min(vert_ids.len()) {
+            selected.push(vert_ids[next]);
+            let source = self.position(vert_ids[next]);
+            match metric {
+                Metric::Euclidean => {
+                    for (i, &vert_id) in vert_ids.iter().enumerate() {
+                        nearest[i] = nearest[i].min((self.position(vert_id) - source).norm());
+                    }
+                }
+                // Dijkstra from the new vertex, which only continues where it improves the distances.
+                Metric::Geodesic => {
+                    nearest[next] = 0.;
+                    // Non-negative floats are ordered like their bits.
+                    let mut queue = BinaryHeap::from([Reverse((0f64.to_bits(), next))]);
+                    while let Some(Reverse((bits, i))) = queue.pop() {
+                        let d = f64::from_bits(bits);
+                        if d > nearest[i] {
+                            continue;
+                        }
+                        for neighbor in self.neighbors(vert_ids[i]) {
+                            let j = index_of[&neighbor];
+                            let d_j = d + self.distance(vert_ids[i], neighbor);
+                            if d_j < nearest[j] {
+                                nearest[j] = d_j;
+                                queue.push(Reverse((d_j.to_bits(), j)));
+                            }
+                        }
+                    }
+                }
+            }
+            next = (0..vert_ids.len()).max_by(|&i, &j| nearest[i].total_cmp(&nearest[j])).unwrap();
+        }
+
+        let normals = self.compute_vertex_normals(NormalWeighting::default());
+        selected
+            .into_iter()
+            .map(|vert_id| {
+                let face = self.faces(vert_id)[0];
+                let corner = self.vertices(face).iter().position(|&v| v == vert_id).unwrap();
+                let nr_corners = self.vertices(face).len();
+                let point = SurfacePoint {
+                    face,
+                    corners: [0, 1, 2].map(|i| (corner + i) % nr_corners),
+                    barycentric: Vector3D::new(1., 0., 0.),
+                    position: self.position(vert_id),
+                    distance: 0.,
+                };
+                let normal = *normals.get_or_panic(vert_id);
+                (vert_id, Sample { point, normal })
+            })
+            .collect()
+    }
+}
+
+// Distance between two samples (see `Metric`).
+fn distance<M: Tag>(a: &Sample<M>, b: &Sample<M>, metric: Metric) -> Float {
+    let chord = (a.point.position - b.point.position).norm();
+    match metric {
+        Metric::Euclidean => chord,
+        // The arc with angle `theta` between the normals at its ends has length `chord * (theta / 2) / sin(theta / 2)`.
+        Metric::Geodesic => {
+            let half = a.normal.angle(&b.normal) / 2.;
+            if half < 1e-6 { chord } else { chord * half / half.sin() }
+        }
+    }
+}
+
+// Samples points on the triangles of the surface, chosen in proportion to their area.
+struct Sampler<'a, M: Tag> {
+    location: FaceLocation<M>,
+    normals: ids::SecMap<VERT, M, Vector3D>,
+    // Cumulative area of the triangles.
+    cumulative: Vec<Float>,
+    area: Float,
+    mesh: &'a Mesh<M>,
+}
+
+impl<'a, M: Tag> Sampler<'a, M> {
+    fn new(mesh: &'a Mesh<M>) -> Self {
+        let location = mesh.bvh();
+        let cumulative = (0..location.nr_triangles())
+            .scan(0., |area, i| {
+                let ([a, b, c], _, _) = location.triangle(i);
+                *area += (b - a).cross(&(c - a)).norm() / 2.;
+                Some(*area)
+            })
+            .collect_vec();
+        Self {
+            area: cumulative.last().copied().unwrap_or(0.),
+            normals: mesh.compute_vertex_normals(NormalWeighting::default()),
+            location,
+            cumulative,
+            mesh,
+        }
+    }
+
+    // Uniform sample in the triangle at fraction `u` (in [0, 1)) of the cumulative area.
+    fn sample(&self, u: Float, rng: &mut StdRng) -> Option<Sample<M>> {
+        if self.area <= 0. {
+            return None;
+        }
+        let index = self.cumulative.partition_point(|&area| area <= u * self.area).min(self.cumulative.len() - 1);
+        let (corners, face_corners, face) = self.location.triangle(index);
+        let (mut s, mut t) = (rng.random::<Float>(), rng.random::<Float>());
+        if s + t > 1. {
+            (s, t) = (1. - s, 1. - t);
+        }
+        let barycentric = Vector3D::new(1. - s - t, s, t);
+        let verts = self.mesh.vertices(face);
+        let position = (0..3).map(|i| corners[i] * barycentric[i]).sum();
+        let normal = (0..3)
+            .map(|i| self.normals.get_or_panic(verts[face_corners[i]]) * barycentric[i])
+            .sum::<Vector3D>();
+        let normal = if normal.norm() > 0. {
+            normal.normalize()
+        } else {
+            (corners[1] - corners[0]).cross(&(corners[2] - corners[0])).normalize()
+        };
+        Some(Sample {
+            point: SurfacePoint {
+                face,
+                corners: face_corners,
+                barycentric,
+                position,
+                distance: 0.,
+            },
+            normal,
+        })
+    }
+}
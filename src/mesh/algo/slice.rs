@@ -0,0 +1,309 @@
+use crate::prelude::*;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+
+// Point where a contour crosses edge `edge`, which goes from above the plane to below it, at `position` = root + t * (toor - root).
+// Vertices on the plane count as above it (so t = 0 at such a vertex, and t < 1 always), which keeps the contours closed and consistent.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ContourPoint<M: Tag> {
+    pub edge: EdgeKey<M>,
+    pub t: Float,
+    pub position: Vector3D,
+}
+
+// Intersections with planes. A plane is given by a point `origin` on it and its normal `normal` (which points to the side above the plane),
+// or by a height along the normal (points at height h satisfy x . normal = h, with the normal of unit length).
+impl<M: Tag> Mesh<M> {
+    // Closed contours where the plane intersects the surface, counterclockwise around the cross-section (viewed from above), such that the outer
+    // contours of the cross-section go counterclockwise and the contours around holes go clockwise. Every contour has at least three points,
+    // and the last point connects to the first. Where the surface only touches the plane (at vertices or edges), there is no contour.
+    #[must_use]
+    pub fn slice(&self, origin: Vector3D, normal: Vector3D) -> Vec<Vec<ContourPoint<M>>> {
+        let normal = normal.normalize();
+        self.contours(normal, &self.heights(normal, origin.dot(&normal)))
+    }
+
+    // Contours of a stack of parallel planes, at the given heights along the normal (see `Mesh::slice`).
+    #[must_use]
+    pub fn slices(&self, normal: Vector3D, heights: &[Float]) -> Vec<Vec<Vec<ContourPoint<M>>>> {
+        let normal = normal.normalize();
+        heights.iter().map(|&height| self.contours(normal, &self.heights(normal, height))).collect()
+    }
+
+    // Split the mesh along the plane into the part above and the part below it, both closed by capping the cross-section (see `Mesh::slice`)
+    // with triangles. Faces that cross the plane are cut along it, and faces on one side are kept as they are. Faces on the plane are left out,
+    // and the part behind them is capped there instead. A part is empty if nothing is on its side.
+    pub fn split(&self, origin: Vector3D, normal: Vector3D) -> Result<(Self, Self), MeshError<M>> {
+        let normal = normal.normalize();
+        let height = origin.dot(&normal);
+        Ok((self.below(-normal, -height)?, self.below(normal, height)?))
+    }
+
+    // Part of the mesh below the plane at the given height, capped with the cross-section (see `Mesh::split`). Vertices on the plane count as
+    // above it, so faces on the plane are not part of it, and the cross-section includes those facing up (but not those facing down).
+    fn below(&self, normal: Vector3D, height: Float) -> Result<Self, MeshError<M>> {
+        let heights = self.heights(normal, height);
+        let is_above = |vert_id| *heights.get_or_panic(vert_id) >= 0.;
+
+        let vert_ids = self.vert_ids();
+        let index_of: HashMap<_, _> = vert_ids.iter().enumerate().map(|(i, &vert_id)| (vert_id, i)).collect();
+        let mut positions = vert_ids.iter().map(|&vert_id| self.position(vert_id)).collect_vec();
+        // The index of the point where the plane crosses an edge (from above to below), which is its root if it lies on the plane.
+        let mut crossings = HashMap::new();
+        let mut point_index = |edge_id: EdgeKey<M>, positions: &mut Vec<Vector3D>| {
+            let point = self.crossing(edge_id, &heights);
+            if point.t == 0. {
+                return index_of[&self.root(edge_id)];
+            }
+            *crossings.entry(edge_id).or_insert_with(|| {
+                positions.push(point.position);
+                positions.len() - 1
+            })
+        };
+
+        let mut below = vec![];
+        for face_id in self.face_ids() {
+            let cuts = self.cuts(face_id, normal, &heights);
+            if cuts.is_empty() {
+                if !is_above(self.root(self.edges(face_id)[0])) {
+                    below.push(self.vertices(face_id).iter().map(|vert_id| index_of[vert_id]).collect_vec());
+                }
+                continue;
+            }
+            // Every part of the face below the plane enters at a crossing downward, follows the face to the next crossing upward, and continues
+            // along the cut to the crossing downward where the cut entered the face.
+            let enter: HashMap<_, _> = cuts.iter().map(|&(down, up)| (up, down)).collect();
+            let mut visited = HashSet::new();
+            for &(start, _) in &cuts {
+                if visited.contains(&start) {
+                    continue;
+                }
+                let mut part = vec![];
+                let mut edge_id = start;
+                loop {
+                    visited.insert(edge_id);
+                    part.push(point_index(edge_id, &mut positions));
+                    part.push(index_of[&self.toor(edge_id)]);
+                    let mut next = self.next(edge_id);
+                    while !enter.contains_key(&next) {
+                        part.push(index_of[&self.toor(next)]);
+                        next = self.next(next);
+                    }
+                    // Crossings upward are stored as the crossing of their twin (from above to below).
+                    part.push(point_index(self.twin(next), &mut positions));
+                    edge_id = enter[&next];
+                    if edge_id == start {
+                        break;
+                    }
+                }
+                below.push(part);
+            }
+        }
+
+        // Cap the part with the cross-section.
+        let contours = self
+            .contours(normal, &heights)
+            .into_iter()
+            .map(|contour| contour.into_iter().map(|point| point_index(point.edge, &mut positions)).collect_vec())
+            .collect_vec();
+        below.extend(cap(&contours, &positions, normal)?.into_iter().map(|triangle| triangle.to_vec()));
+
+        // Parts of faces that collapse onto the plane (where vertices lie on it) are left out.
+        let faces = below
+            .into_iter()
+            .map(|face| face.into_iter().dedup().collect_vec())
+            .map(|mut face| {
+                while face.len() > 1 && face.first() == face.last() {
+                    face.pop();
+                }
+                face
+            })
+            .filter(|face| face.len() >= 3)
+            .collect_vec();
+        Ok(Self::from(&faces, &positions)?.0)
+    }
+
+    // Height of every vertex above the plane. Vertices within rounding error of the plane (see `geom::tolerance`) are on it.
+    fn heights(&self, normal: Vector3D, height: Float) -> ids::SecMap<VERT, M, Float> {
+        let positions = self.vert_ids().into_iter().map(|vert_id| self.position(vert_id)).collect_vec();
+        let tolerance = geom::tolerance(geom::scale(&positions).max(height.abs()));
+        self.vert_attribute(|vert_id| {
+            let h = self.position(vert_id).dot(&normal) - height;
+            if h.abs() <= tolerance { 0. } else { h }
+        })
+    }
+
+    // Crossing of the plane with an edge from above the plane to below it.
+    fn crossing(&self, edge_id: EdgeKey<M>, heights: &ids::SecMap<VERT, M, Float>) -> ContourPoint<M> {
+        let (root, toor) = (self.root(edge_id), self.toor(edge_id));
+        let (a, b) = (*heights.get_or_panic(root), *heights.get_or_panic(toor));
+        let t = a / (a - b);
+        ContourPoint {
+            edge: edge_id,
+            t,
+            position: self.position(root) + (self.position(toor) - self.position(root)) * t,
+        }
+    }
+
+    // Where the plane cuts a face, as pairs of a crossing downward (where the cut enters the face) and a crossing upward (where it leaves the face).
+    // A convex face is cut at most once, from its crossing downward to the next crossing upward around the face. Other faces can be cut more
+    // than once, and their crossings are paired in order along the line where the plane cuts the face, along which the cuts enter the face at
+    // crossings downward and leave it at crossings upward (falling back to the order around the face if rounding makes them inconsistent).
+    fn cuts(&self, face_id: FaceKey<M>, normal: Vector3D, heights: &ids::SecMap<VERT, M, Float>) -> Vec<(EdgeKey<M>, EdgeKey<M>)> {
+        let is_above = |vert_id| *heights.get_or_panic(vert_id) >= 0.;
+        let downward = |edge_id| is_above(self.root(edge_id)) && !is_above(self.toor(edge_id));
+        let upward = |edge_id| !is_above(self.root(edge_id)) && is_above(self.toor(edge_id));
+        let edges = self.edges(face_id);
+        let around = edges
+            .iter()
+            .filter(|&&edge_id| downward(edge_id))
+            .map(|&down| {
+                let mut up = self.next(down);
+                while !upward(up) {
+                    up = self.next(up);
+                }
+                (down, up)
+            })
+            .collect_vec();
+        if around.len() < 2 {
+            return around;
+        }
+
+        let direction = normal.cross(&self.normal(face_id));
+        let mut crossings = edges
+            .iter()
+            .filter(|&&edge_id| downward(edge_id) || upward(edge_id))
+            .map(|&edge_id| {
+                let is_down = downward(edge_id);
+                let point = self.crossing(if is_down { edge_id } else { self.twin(edge_id) }, heights);
+                (point.position.dot(&direction), is_down, edge_id)
+            })
+            .collect_vec();
+        crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut cuts = vec![];
+        let mut open = None;
+        for i in 0..crossings.len() {
+            let wanted = open.is_none();
+            // Crossings at the same position (at a vertex on the plane) are taken in the order that alternates entering and leaving the face.
+            if let Some(j) = (i..crossings.len())
+                .take_while(|&j| crossings[j].0 == crossings[i].0)
+                .find(|&j| crossings[j].1 == wanted)
+            {
+                crossings.swap(i, j);
+            }
+            let (_, is_down, edge_id) = crossings[i];
+            if is_down != wanted {
+                return around;
+            }
+            match open.take() {
+                None => open = Some(edge_id),
+                Some(down) => cuts.push((down, edge_id)),
+            }
+        }
+        if open.is_some() {
+            return around;
+        }
+        cuts
+    }
+
+    // Contours through the crossings of the edges, from every crossing downward along the cut through its face to the crossing upward
+    // (see `Mesh::cuts`), and on through the neighboring face (see `Mesh::slice`).
+    fn contours(&self, normal: Vector3D, heights: &ids::SecMap<VERT, M, Float>) -> Vec<Vec<ContourPoint<M>>> {
+        let cuts: HashMap<_, _> = self.face_ids().into_iter().flat_map(|face_id| self.cuts(face_id, normal, heights)).collect();
+        let mut visited = HashSet::new();
+        let mut contours = vec![];
+        for start in self.edge_ids() {
+            if !cuts.contains_key(&start) || visited.contains(&start) {
+                continue;
+            }
+            let mut contour: Vec<ContourPoint<M>> = vec![];
+            let mut edge_id = start;
+            loop {
+                visited.insert(edge_id);
+                let point = self.crossing(edge_id, heights);
+                // Consecutive crossings at the same vertex on the plane are the same point.
+                if !contour
+                    .last()
+                    .is_some_and(|last| last.t == 0. && point.t == 0. && self.root(last.edge) == self.root(edge_id))
+                {
+                    contour.push(point);
+                }
+                edge_id = self.twin(cuts[&edge_id]);
+                if edge_id == start {
+                    break;
+                }
+            }
+            while contour.len() > 1
+                && contour[0].t == 0.
+                && contour[contour.len() - 1].t == 0.
+                && self.root(contour[0].edge) == self.root(contour[contour.len() - 1].edge)
+            {
+                contour.pop();
+            }
+            if contour.len() >= 3 {
+                contours.push(contour);
+            }
+        }
+        contours
+    }
+}
+
+// Triangles (counterclockwise around the normal) that fill the region bounded by the contours (with indices into `positions`) in the plane:
+// counterclockwise contours bound the region from the outside, and clockwise contours are holes in the smallest contour around them.
+fn cap<M: Tag>(contours: &[Vec<usize>], positions: &[Vector3D], normal: Vector3D) -> Result<Vec<[usize; 3]>, MeshError<M>> {
+    let u = normal.cross(&Vector3D::ith(normal.iamin(), 1.)).normalize();
+    let v = normal.cross(&u);
+    let project = |index: usize| Vector2D::new(positions[index].dot(&u), positions[index].dot(&v));
+    let area = |contour: &[usize]| {
+        (0..contour.len())
+            .map(|i| project(contour[i]).perp(&project(contour[(i + 1) % contour.len()])))
+            .sum::<Float>()
+            / 2.
+    };
+    let inside = |p: Vector2D, contour: &[usize]| {
+        // Crossing number of a ray in the x-direction.
+        (0..contour.len())
+            .filter(|&i| {
+                let (a, b) = (project(contour[i]), project(contour[(i + 1) % contour.len()]));
+                (a.y > p.y) != (b.y > p.y) && p.x < a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x)
+            })
+            .count()
+            % 2
+            == 1
+    };
+
+    let (outer, holes): (Vec<_>, Vec<_>) = contours.iter().partition(|contour| area(contour) > 0.);
+    let mut children = vec![vec![]; outer.len()];
+    for hole in holes {
+        let Some(&p) = hole.iter().find(|&p| outer.iter().all(|contour| !contour.contains(p))) else {
+            continue;
+        };
+        let parent = (0..outer.len())
+            .filter(|&i| inside(project(p), outer[i]))
+            .min_by(|&i, &j| area(outer[i]).total_cmp(&area(outer[j])));
+        if let Some(parent) = parent {
+            children[parent].push(hole);
+        }
+    }
+
+    let mut triangles = vec![];
+    for (contour, holes) in outer.into_iter().zip(children) {
+        let indices = contour.iter().chain(holes.iter().copied().flatten()).copied().collect_vec();
+        let coordinates = indices.iter().flat_map(|&index| [project(index).x, project(index).y]).collect_vec();
+        let starts = holes.iter().scan(contour.len(), |start, hole| {
+            let current = *start;
+            *start += hole.len();
+            Some(current)
+        });
+        let result = earcutr::earcut(&coordinates, &starts.collect_vec(), 2).map_err(|e| MeshError::Unknown(format!("Cannot cap the cross-section: {e:?}")))?;
+        for t in result.chunks_exact(3) {
+            let [a, b, c] = [t[0], t[1], t[2]].map(|i| indices[i]);
+            triangles.push(if geom::orient2d(project(a), project(b), project(c)) < 0. {
+                [a, c, b]
+            } else {
+                [a, b, c]
+            });
+        }
+    }
+    Ok(triangles)
+}
@@ -1,12 +1,14 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 
 use crate::prelude::*;
-use earcutr;
+
+// Face of the original mesh of every new face of a triangulated mesh (faces that were already triangles are not included).
+pub type FaceParents<M> = HashMap<FaceKey<M>, FaceKey<M>>;
 
 // Given an arbitrary polygonal mesh, triangulate all faces to obtain a triangular mesh.
 // This is useful for rendering or further processing.
 impl<M: Tag> Mesh<M> {
-    pub fn triangulate(&self) -> Result<(Self, HashMap<FaceKey<M>, FaceKey<M>>), MeshError<M>> {
+    pub fn triangulate(&self) -> Result<(Self, FaceParents<M>), MeshError<M>> {
         let mut new_mesh = self.clone();
         let mut new_faces = HashMap::new();
 
@@ -27,16 +29,42 @@ impl<M: Tag> Mesh<M> {
         Ok((new_mesh, new_faces))
     }
 
+    // Triangulation of a face (without modifying the mesh), as triples of indices into its corners (`vertices(face)`), counterclockwise.
+    pub fn face_triangles(&self, face: FaceKey<M>) -> Result<Vec<[usize; 3]>, MeshError<M>> {
+        let degree = self.vertices(face).len();
+        if degree < 3 {
+            return Err(MeshError::FaceNotPolygon(face));
+        }
+        if degree == 3 {
+            return Ok(vec![[0, 1, 2]]);
+        }
+
+        // Triangulate the face in its best-fit plane (ear clipping), such that concave faces are handled correctly.
+        let projected = self.project(face);
+        let positions = projected.iter().flat_map(|&v| [v.x, v.y]).collect::<Vec<_>>();
+        let indices = earcutr::earcut(&positions, &[], 2).map_err(|e| MeshError::Unknown(format!("Cannot triangulate {face:?}: {e:?}")))?;
+        if indices.len() != 3 * (degree - 2) {
+            return Err(MeshError::FaceNotSimple(face));
+        }
+
+        // The projected face is counterclockwise, so every triangle should be counterclockwise as well.
+        Ok(indices
+            .chunks_exact(3)
+            .map(|t| {
+                if geom::orient2d(projected[t[0]], projected[t[1]], projected[t[2]]) < 0. {
+                    [t[0], t[2], t[1]]
+                } else {
+                    [t[0], t[1], t[2]]
+                }
+            })
+            .collect())
+    }
+
     pub fn triangulate_face(&mut self, face: FaceKey<M>) -> Result<Vec<FaceKey<M>>, MeshError<M>> {
         let edges = self.edges(face);
         let original_edges = self.edges(face).iter().map(|&e| self.vertices(e)).collect::<Vec<_>>();
         let original_vertices = self.vertices(face);
-
-        let positions = self.project(face).iter().flat_map(|&v| vec![v.x, v.y]).collect::<Vec<_>>();
-        // let binding = earcutr::earcut(&positions, &[], 2).unwrap();
-
-        // let triangles = binding.chunks(3).collect::<Vec<_>>();
-        let triangles = [[1, 0, 3], [3, 2, 1]]; // For testing purposes, replace with actual triangulation for higher degree faces.
+        let triangles = self.face_triangles(face)?;
 
         // Remove the old face
         self.faces.remove(face);
@@ -49,12 +77,6 @@ impl<M: Tag> Mesh<M> {
 
             let mut edge_ids = vec![];
 
-            let v1 = original_vertices[triangle[0]];
-            let v2 = original_vertices[triangle[1]];
-            let v3 = original_vertices[triangle[2]];
-
-            let triangle = vec![triangle[0], triangle[2], triangle[1]];
-
             for i in 0..3 {
                 let v1 = triangle[i];
                 let v2 = triangle[(i + 1) % triangle.len()];
@@ -9,7 +9,7 @@ impl<M: Tag> Mesh<M> {
     }
 
     // This is a struct that defines an embedded mesh with vertices (with position), edges, and faces (with clockwise ordering).
-    pub fn from(faces: &[Vec<usize>], positions: &[Vector3D]) -> Result<(Self, ids::IdMap<VERT, M>, ids::IdMap<FACE, M>), MeshError<M>> {
+    pub fn from(faces: &[Vec<usize>], positions: &[Vector3D]) -> Result<MeshWithIds<M>, MeshError<M>> {
         let mut mesh = Self::empty();
 
         // 1. Create the vertices.
@@ -90,8 +90,8 @@ impl<M: Tag> Mesh<M> {
         mesh.assert_references();
         mesh.assert_invariants();
 
+        // Whether the faces are planar and simple is left to the caller (see `Mesh::is_polygonal`).
         // mesh.is_connected();
-        mesh.is_polygonal();
 
         Ok((mesh, vertex_pointers, face_pointers))
     }
@@ -16,6 +16,9 @@ pub type FaceKey<M> = ids::Key<FACE, M>;
 pub struct EDGE;
 pub type EdgeKey<M> = ids::Key<EDGE, M>;
 
+// Mesh together with its vertex and face of every input index (see `Mesh::from`).
+pub type MeshWithIds<M> = (Mesh<M>, ids::IdMap<VERT, M>, ids::IdMap<FACE, M>);
+
 #[derive(Error, Debug, Clone, Serialize, Deserialize)]
 pub enum MeshError<M> {
     #[error("({0}, {1}) does not have a twin (mesh is not a closed 2-manifold)")]
@@ -32,13 +35,15 @@ pub enum MeshError<M> {
     FaceNotPlanar(FaceKey<M>),
     #[error("{0} is not simple (edges intersect)")]
     FaceNotSimple(FaceKey<M>),
+    #[error("{0} is not a triangle")]
+    FaceNotTriangle(FaceKey<M>),
     #[error("Unknown error ({0})")]
     Unknown(String),
 }
 
 // Define a new trait that combines all required supertraits.
-pub trait Tag: Default + Debug + Clone + Copy + PartialEq + Eq + std::hash::Hash {}
-impl<T> Tag for T where T: Default + Debug + Clone + Copy + PartialEq + Eq + std::hash::Hash {}
+pub trait Tag: Default + Debug + Clone + Copy + PartialEq + Eq + std::hash::Hash + Send + Sync {}
+impl<T> Tag for T where T: Default + Debug + Clone + Copy + PartialEq + Eq + std::hash::Hash + Send + Sync {}
 
 #[macro_export]
 macro_rules! define_tag {
@@ -135,6 +140,46 @@ impl<M: Tag> Mesh<M> {
         self.faces.ids().choose_multiple(&mut rand::rng(), n)
     }
 
+    // Evaluate `f` for every vertex, and collect the results in an attribute map.
+    #[must_use]
+    pub fn vert_attribute<V>(&self, f: impl Fn(VertKey<M>) -> V) -> ids::SecMap<VERT, M, V> {
+        let mut map = ids::SecMap::new();
+        for vert_id in self.vert_ids() {
+            map.insert(vert_id, f(vert_id));
+        }
+        map
+    }
+
+    // Evaluate `f` for every face, and collect the results in an attribute map.
+    #[must_use]
+    pub fn face_attribute<V>(&self, f: impl Fn(FaceKey<M>) -> V) -> ids::SecMap<FACE, M, V> {
+        let mut map = ids::SecMap::new();
+        for face_id in self.face_ids() {
+            map.insert(face_id, f(face_id));
+        }
+        map
+    }
+
+    // Map from the vertices to consecutive indices (in the order of `vert_ids`). Stable as long as no vertices are added or removed.
+    #[must_use]
+    pub fn vert_index_map(&self) -> ids::IdMap<VERT, M> {
+        let mut vert_map = ids::IdMap::new();
+        for (i, vert_id) in self.vert_ids().into_iter().enumerate() {
+            vert_map.insert(i, vert_id);
+        }
+        vert_map
+    }
+
+    // Map from the faces to consecutive indices (in the order of `face_ids`). Stable as long as no faces are added or removed.
+    #[must_use]
+    pub fn face_index_map(&self) -> ids::IdMap<FACE, M> {
+        let mut face_map = ids::IdMap::new();
+        for (i, face_id) in self.face_ids().into_iter().enumerate() {
+            face_map.insert(i, face_id);
+        }
+        face_map
+    }
+
     // TODO: make this more ergonamic
     pub fn neighbor_function_primal(&self) -> impl Fn(VertKey<M>) -> Vec<VertKey<M>> + '_ {
         |v_id| self.neighbors(v_id)
@@ -42,14 +42,58 @@ impl<M: Tag> Mesh<M> {
             .find(|&face_id| verts.iter().all(|&vert_id| self.faces(vert_id).contains(&face_id)))
     }
 
-    // Vector area of a given face.
+    // Vector area of a given face (Newell's method), pointing along the normal of the face, with magnitude equal to its area.
+    // This is well-defined for concave and non-planar faces as well. Positions are taken relative to the first corner, to limit cancellation.
     #[must_use]
     pub fn vector_area(&self, id: FaceKey<M>) -> Vector3D {
-        self.edges(id).iter().fold(Vector3D::zeros(), |sum, &edge_id| {
-            let u = self.vector(self.twin(edge_id));
-            let v = self.vector(self.next(edge_id));
-            sum + u.cross(&v)
-        })
+        let corners = self.vertices(id).into_iter().map(|vert_id| self.position(vert_id)).collect_vec();
+        let reference = corners[0];
+        corners
+            .iter()
+            .circular_tuple_windows()
+            .fold(Vector3D::zeros(), |sum, (&p, &q)| sum + (p - reference).cross(&(q - reference)))
+            / 2.
+    }
+
+    // Least-squares plane through the corners of a given face, as (point, normal).
+    // The point is the centroid of the corners, the normal is oriented like the normal of the face.
+    #[must_use]
+    pub fn best_fit_plane(&self, id: FaceKey<M>) -> (Vector3D, Vector3D) {
+        let centroid = self.position(id);
+        let normal = self.normal(id);
+        let covariance = self
+            .vertices(id)
+            .into_iter()
+            .map(|vert_id| self.position(vert_id) - centroid)
+            .fold(nalgebra::Matrix3::zeros(), |sum, d| sum + d * d.transpose());
+
+        // The direction of least variance. For (nearly) degenerate faces, such as slivers, this is not unique or cannot be computed accurately
+        // (the error of the eigenvector grows with the ratio of the largest eigenvalue to the gap), and the Newell normal is used instead.
+        let eigen = covariance.symmetric_eigen();
+        let mut order = [0, 1, 2];
+        order.sort_by(|&i, &j| eigen.eigenvalues[i].total_cmp(&eigen.eigenvalues[j]));
+        if eigen.eigenvalues[order[1]] - eigen.eigenvalues[order[0]] <= 1e-6 * eigen.eigenvalues[order[2]] {
+            return (centroid, normal);
+        }
+        let fitted = eigen.eigenvectors.column(order[0]).into_owned();
+        (centroid, if fitted.dot(&normal) < 0. { -fitted } else { fitted })
+    }
+
+    // Planarity of a given face: the largest distance of a corner to the best-fit plane, relative to the size (bounding box diagonal) of the face.
+    // This is zero for triangles, and zero up to rounding for planar faces.
+    #[must_use]
+    pub fn planarity(&self, id: FaceKey<M>) -> Float {
+        let corners = self.vertices(id).into_iter().map(|vert_id| self.position(vert_id)).collect_vec();
+        if corners.len() <= 3 {
+            return 0.;
+        }
+        let (point, normal) = self.best_fit_plane(id);
+        let deviation = corners.iter().map(|&p| (p - point).dot(&normal).abs()).fold(0., Float::max);
+        let (min, max) = corners
+            .iter()
+            .fold((Vector3D::repeat(INF), Vector3D::repeat(NEG_INF)), |(min, max), p| (min.inf(p), max.sup(p)));
+        let size = (max - min).norm();
+        if size > 0. { deviation / size } else { 0. }
     }
 }
 
@@ -63,24 +107,16 @@ impl<M: Tag> HasPosition<FACE, M> for Mesh<M> {
 }
 
 impl<M: Tag> HasNormal<FACE, M> for Mesh<M> {
+    // Normal of a given face, based on its vector area (such that concave and non-planar faces are handled correctly).
     fn normal(&self, id: FaceKey<M>) -> Vector3D {
-        // TODO: Make this better for non-planar faces.
-        let vertices = self.vertices(id);
-        let (u, v, w) = (vertices[0], vertices[1], vertices[2]);
-        let p_u = self.position(u);
-        let p_v = self.position(v);
-        let p_w = self.position(w);
-        let p = p_v - p_u;
-        let q = p_w - p_u;
-        let normal_vector = p.cross(&q).normalize();
-        normal_vector
+        self.vector_area(id).normalize()
     }
 }
 
 impl<M: Tag> HasSize<FACE, M> for Mesh<M> {
     // Area of a given face.
     fn size(&self, id: FaceKey<M>) -> Float {
-        self.vector_area(id).magnitude() / 2.0
+        self.vector_area(id).magnitude()
     }
 }
 
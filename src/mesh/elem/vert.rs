@@ -2,6 +2,23 @@ use crate::prelude::*;
 use core::panic;
 use itertools::Itertools;
 
+// Weighting of the normals of the faces around a vertex, when computing the normal of the vertex.
+//      Uniform: every face counts equally (the default)
+//      Area: faces are weighted by their area
+//      Angle: faces are weighted by their interior angle at the vertex (independent of the tessellation)
+//      MaxFormula: corners are weighted by their sine divided by the lengths of the adjacent edges (Max, 1999), exact for vertices on a sphere
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum NormalWeighting {
+    #[default]
+    Uniform,
+    Area,
+    Angle,
+    MaxFormula,
+}
+
+// Name of the vertex attribute in which vertex normals are cached (see `Mesh::cache_vertex_normals`).
+pub const VERTEX_NORMALS: &str = "normal";
+
 impl<M: Tag> Mesh<M> {
     #[must_use]
     pub fn vrep(&self, id: VertKey<M>) -> EdgeKey<M> {
@@ -76,6 +93,41 @@ impl<M: Tag> Mesh<M> {
             .collect_vec()
     }
 
+    // Normal of a given vertex, as weighted sum of the normals of the faces around it.
+    #[must_use]
+    pub fn vertex_normal(&self, id: VertKey<M>, weighting: NormalWeighting) -> Vector3D {
+        self.edges(id)
+            .into_iter()
+            .map(|outgoing_edge_id| {
+                // The corner at the vertex, in the face of the incoming edge (spanned by the next and the previous corner of that face).
+                let incoming_edge_id = self.twin(outgoing_edge_id);
+                let face_id = self.face(incoming_edge_id);
+                let (to_next, to_prev) = (self.vector(self.next(incoming_edge_id)), self.vector(outgoing_edge_id));
+                match weighting {
+                    NormalWeighting::Uniform => self.normal(face_id),
+                    NormalWeighting::Area => self.vector_area(face_id),
+                    NormalWeighting::Angle => self.normal(face_id) * to_next.angle(&to_prev),
+                    NormalWeighting::MaxFormula => to_next.cross(&to_prev) / (to_next.norm_squared() * to_prev.norm_squared()),
+                }
+            })
+            .sum::<Vector3D>()
+            .normalize()
+    }
+
+    // Normals of all vertices, to be computed once and reused (see `Mesh::cache_vertex_normals`).
+    #[must_use]
+    pub fn compute_vertex_normals(&self, weighting: NormalWeighting) -> ids::SecMap<VERT, M, Vector3D> {
+        self.vert_attribute(|vert_id| self.vertex_normal(vert_id, weighting))
+    }
+
+    // Compute the normals of all vertices and cache them in the attribute `VERTEX_NORMALS`, which is used (instead of computing the normals
+    // again) by `Mesh::bevy_with_attributes` and `Mesh::to_obj_with_attributes`, and stored in native files.
+    pub fn cache_vertex_normals(&self, weighting: NormalWeighting, attributes: &mut Attributes<M>) {
+        attributes
+            .verts
+            .insert(VERTEX_NORMALS.to_owned(), Attribute::Vector(self.compute_vertex_normals(weighting)));
+    }
+
     // Returns the edge between the two vertices. Returns None if the vertices are not connected.
     #[must_use]
     pub fn edge_between_verts(&self, id_a: VertKey<M>, id_b: VertKey<M>) -> Option<(EdgeKey<M>, EdgeKey<M>)> {
@@ -109,8 +161,9 @@ impl<M: Tag> HasPosition<VERT, M> for Mesh<M> {
 }
 
 impl<M: Tag> HasNormal<VERT, M> for Mesh<M> {
+    // Normal of a given vertex, with the default (uniform) weighting.
     fn normal(&self, id: VertKey<M>) -> Vector3D {
-        self.faces(id).iter().map(|&face_id| self.normal(face_id)).sum::<Vector3D>().normalize()
+        self.vertex_normal(id, NormalWeighting::default())
     }
 }
 
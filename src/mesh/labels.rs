@@ -0,0 +1,43 @@
+use crate::prelude::*;
+use std::collections::HashSet;
+
+// Labels and feature flags of a surface mesh, as they are stored by FEM and meshing file formats (Medit, Gmsh).
+//      verts: reference of each vertex
+//      faces: reference (physical tag) of each face
+//      ridges: sharp edges, both half-edges of a ridge are flagged
+//      corners: sharp vertices
+#[derive(Clone, Debug, Default)]
+pub struct Labels<M: Tag> {
+    pub verts: ids::SecMap<VERT, M, i64>,
+    pub faces: ids::SecMap<FACE, M, i64>,
+    pub ridges: HashSet<EdgeKey<M>>,
+    pub corners: HashSet<VertKey<M>>,
+}
+
+// Mesh together with its vertex and face of every input index (see `Mesh::from`) and its labels.
+pub type LabeledMeshWithIds<M> = (Mesh<M>, ids::IdMap<VERT, M>, ids::IdMap<FACE, M>, Labels<M>);
+
+impl<M: Tag> Labels<M> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Flag the edge between `a` and `b` (both half-edges) as a ridge. Returns false if the vertices are not connected.
+    pub fn add_ridge(&mut self, mesh: &Mesh<M>, a: VertKey<M>, b: VertKey<M>) -> bool {
+        mesh.edge_between_verts(a, b).is_some_and(|(edge_a, edge_b)| {
+            self.ridges.insert(edge_a);
+            self.ridges.insert(edge_b);
+            true
+        })
+    }
+
+    // Returns every ridge once (as one of its half-edges).
+    #[must_use]
+    pub fn unique_ridges(&self, mesh: &Mesh<M>) -> Vec<EdgeKey<M>> {
+        mesh.edge_ids()
+            .into_iter()
+            .filter(|&edge_id| self.ridges.contains(&edge_id) && (edge_id < mesh.twin(edge_id) || !self.ridges.contains(&mesh.twin(edge_id))))
+            .collect()
+    }
+}
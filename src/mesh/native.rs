@@ -0,0 +1,555 @@
+use crate::mesh::algo::location::face::TrianglePart;
+use crate::prelude::*;
+use bvh::{aabb::Aabb, bvh::BvhNode};
+use flate2::{Compression, read::ZlibDecoder, write::ZlibEncoder};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+// Native binary file format (.mehsh), which stores a mesh with its (stable) keys and attributes.
+//
+// Header (12 bytes, never compressed):
+//      magic       6 bytes     "MEHSH\0"
+//      version     u16         format version (see `VERSION`)
+//      flags       u32         bit 0: payload is zlib compressed
+//                              bit 1: payload contains a face location (BVH)
+//                              bit 2: vertex location (kd-tree) requested, which is rebuilt when reading (see below)
+//
+// Payload (all numbers are little-endian, keys are stored as `Key::to_bits`, strings as a u64 length followed by UTF-8 bytes):
+//      vertices    u64 count, then per vertex: key, position (3 x f64), representative edge
+//                  u64 count, then per free slot: slot index (u64, since version 3, see `IdxMap::free_slots`)
+//      edges       u64 count, then per edge: key, value (u8), root, face, next, twin
+//                  u64 count, then per free slot: slot index (u64, since version 3)
+//      faces       u64 count, then per face: key, value (u8), representative edge
+//                  u64 count, then per free slot: slot index (u64, since version 3)
+//      attributes  for vertices, edges and faces (in that order): u64 count, then per attribute:
+//                      name (string), kind (u8: 0 = scalar f64, 1 = vector 3 x f64, 2 = integer i64), u64 count, then per element: key, value
+//      face location (if flagged)
+//                  u64 count, then per node: tag (u8: 0 = leaf, 1 = inner), parent (u64), then
+//                      leaf: shape index (u64)
+//                      inner: left child (u64), left aabb (6 x f64), right child (u64), right aabb (6 x f64)
+//                  u64 count, then per triangle: corners (9 x f64), corners in the face (3 x u64, since version 2), node index (u64), face
+//      vertex location (if flagged)
+//                  no data, the kd-tree is rebuilt from the vertices when reading (its internal layout is not exposed)
+//
+// When the format changes, `VERSION` is increased and reading older versions is handled in `read_payload`.
+pub const VERSION: u16 = 3;
+const MAGIC: &[u8; 6] = b"MEHSH\0";
+const FLAG_COMPRESSED: u32 = 1;
+const FLAG_FACE_LOCATION: u32 = 1 << 1;
+const FLAG_VERT_LOCATION: u32 = 1 << 2;
+
+// A named attribute (per vertex, edge or face) stored alongside the mesh.
+#[derive(Clone, Debug)]
+pub enum Attribute<K: Eq + std::hash::Hash, M: Tag> {
+    Scalar(ids::SecMap<K, M, Float>),
+    Vector(ids::SecMap<K, M, Vector3D>),
+    Integer(ids::SecMap<K, M, i64>),
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Attributes<M: Tag> {
+    pub verts: HashMap<String, Attribute<VERT, M>>,
+    pub edges: HashMap<String, Attribute<EDGE, M>>,
+    pub faces: HashMap<String, Attribute<FACE, M>>,
+}
+
+impl<M: Tag> Attributes<M> {
+    // Vertex normals cached by `Mesh::cache_vertex_normals`, if any.
+    #[must_use]
+    pub fn vertex_normals(&self) -> Option<&ids::SecMap<VERT, M, Vector3D>> {
+        match self.verts.get(VERTEX_NORMALS) {
+            Some(Attribute::Vector(normals)) => Some(normals),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NativeOptions {
+    // Compress the payload (zlib).
+    pub compress: bool,
+    // Store the face location (BVH) of the mesh, such that it does not have to be rebuilt.
+    pub face_location: bool,
+    // Return a vertex location (kd-tree) when reading the file. It is not stored, but rebuilt from the vertices when reading.
+    pub vert_location: bool,
+}
+
+// Contents of a native file.
+#[derive(Clone, Debug)]
+pub struct NativeFile<M: Tag> {
+    pub mesh: Mesh<M>,
+    pub attributes: Attributes<M>,
+    pub face_location: Option<FaceLocation<M>>,
+    pub vert_location: Option<VertLocation<M>>,
+}
+
+impl<M: Tag> Mesh<M> {
+    pub fn to_mehsh(&self, path: &PathBuf, attributes: &Attributes<M>, options: NativeOptions) -> Result<(), std::io::Error> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        self.write_mehsh(&mut file, attributes, options)?;
+        file.flush()
+    }
+
+    pub fn from_mehsh(path: &PathBuf) -> Result<NativeFile<M>, MeshError<M>> {
+        match std::fs::File::open(path) {
+            Ok(file) => Self::read_mehsh(std::io::BufReader::new(file))
+                .map_err(|e| MeshError::Unknown(format!("Something went wrong while reading the mehsh file: {path:?}\nErr: {e}"))),
+            Err(e) => Err(MeshError::Unknown(format!("Cannot read file: {path:?}\nErr: {e}"))),
+        }
+    }
+
+    pub fn write_mehsh(&self, mut writer: impl Write, attributes: &Attributes<M>, options: NativeOptions) -> Result<(), std::io::Error> {
+        let mut payload = Encoder::default();
+
+        payload.u64(self.nr_verts());
+        for vert_id in self.vert_ids() {
+            payload.key(vert_id);
+            payload.vector(self.position(vert_id));
+            payload.key(self.vrep(vert_id));
+        }
+        payload.slots(&self.verts.free_slots());
+
+        payload.u64(self.nr_edges());
+        for edge_id in self.edge_ids() {
+            payload.key(edge_id);
+            payload.bytes(&[self.edges.get(edge_id).copied().unwrap_or_default()]);
+            payload.key(self.root(edge_id));
+            payload.key(self.face(edge_id));
+            payload.key(self.next(edge_id));
+            payload.key(self.twin(edge_id));
+        }
+        payload.slots(&self.edges.free_slots());
+
+        payload.u64(self.nr_faces());
+        for face_id in self.face_ids() {
+            payload.key(face_id);
+            payload.bytes(&[self.faces.get(face_id).copied().unwrap_or_default()]);
+            payload.key(self.frep(face_id));
+        }
+        payload.slots(&self.faces.free_slots());
+
+        payload.attributes(&attributes.verts);
+        payload.attributes(&attributes.edges);
+        payload.attributes(&attributes.faces);
+
+        if options.face_location {
+            let face_location = self.bvh();
+            let (nodes, triangles) = face_location.parts();
+            payload.u64(nodes.len());
+            for node in nodes {
+                match node {
+                    BvhNode::Leaf { parent_index, shape_index } => {
+                        payload.bytes(&[0]);
+                        payload.u64(*parent_index);
+                        payload.u64(*shape_index);
+                    }
+                    BvhNode::Node {
+                        parent_index,
+                        child_l_index,
+                        child_l_aabb,
+                        child_r_index,
+                        child_r_aabb,
+                    } => {
+                        payload.bytes(&[1]);
+                        payload.u64(*parent_index);
+                        payload.u64(*child_l_index);
+                        payload.aabb(child_l_aabb);
+                        payload.u64(*child_r_index);
+                        payload.aabb(child_r_aabb);
+                    }
+                }
+            }
+            payload.u64(triangles.len());
+            for (corners, face_corners, node_index, face_id) in triangles {
+                for corner in corners {
+                    payload.vector(corner);
+                }
+                for face_corner in face_corners {
+                    payload.u64(face_corner);
+                }
+                payload.u64(node_index);
+                payload.key(face_id);
+            }
+        }
+
+        let mut flags = 0;
+        if options.compress {
+            flags |= FLAG_COMPRESSED;
+        }
+        if options.face_location {
+            flags |= FLAG_FACE_LOCATION;
+        }
+        if options.vert_location {
+            flags |= FLAG_VERT_LOCATION;
+        }
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&flags.to_le_bytes())?;
+        if options.compress {
+            let mut encoder = ZlibEncoder::new(writer, Compression::default());
+            encoder.write_all(&payload.0)?;
+            encoder.finish()?;
+        } else {
+            writer.write_all(&payload.0)?;
+        }
+        Ok(())
+    }
+
+    pub fn read_mehsh(mut reader: impl Read) -> Result<NativeFile<M>, std::io::Error> {
+        let mut header = [0; 12];
+        reader.read_exact(&mut header)?;
+        if &header[..6] != MAGIC {
+            return Err(invalid("not a mehsh file"));
+        }
+        let version = u16::from_le_bytes([header[6], header[7]]);
+        let flags = u32::from_le_bytes([header[8], header[9], header[10], header[11]]);
+        if version == 0 || version > VERSION {
+            return Err(invalid(&format!("unsupported version {version} (latest supported version is {VERSION})")));
+        }
+
+        let mut payload = vec![];
+        if flags & FLAG_COMPRESSED == 0 {
+            reader.read_to_end(&mut payload)?;
+        } else {
+            ZlibDecoder::new(reader).read_to_end(&mut payload)?;
+        }
+
+        Self::read_payload(&mut Decoder(&payload), version, flags)
+    }
+
+    fn read_payload(payload: &mut Decoder, version: u16, flags: u32) -> Result<NativeFile<M>, std::io::Error> {
+        let mut mesh = Self::default();
+
+        let mut verts = vec![];
+        for _ in 0..payload.u64()? {
+            let vert_id = payload.key()?;
+            verts.push((vert_id, payload.vector()?));
+            mesh.vert_repr.insert(vert_id, payload.key()?);
+        }
+        let free_verts = payload.slots(version, &verts)?;
+
+        let mut edges = vec![];
+        for _ in 0..payload.u64()? {
+            let edge_id = payload.key()?;
+            edges.push((edge_id, payload.byte()?));
+            mesh.edge_root.insert(edge_id, payload.key()?);
+            mesh.edge_face.insert(edge_id, payload.key()?);
+            mesh.edge_next.insert(edge_id, payload.key()?);
+            mesh.edge_twin.insert(edge_id, payload.key()?);
+        }
+        let free_edges = payload.slots(version, &edges)?;
+
+        let mut faces = vec![];
+        for _ in 0..payload.u64()? {
+            let face_id = payload.key()?;
+            faces.push((face_id, payload.byte()?));
+            mesh.face_repr.insert(face_id, payload.key()?);
+        }
+        let free_faces = payload.slots(version, &faces)?;
+
+        mesh.verts = ids::IdxMap::from_entries(verts, &free_verts).ok_or_else(|| invalid("invalid vertex keys"))?;
+        mesh.edges = ids::IdxMap::from_entries(edges, &free_edges).ok_or_else(|| invalid("invalid edge keys"))?;
+        mesh.faces = ids::IdxMap::from_entries(faces, &free_faces).ok_or_else(|| invalid("invalid face keys"))?;
+
+        let attributes = Attributes {
+            verts: payload.attributes()?,
+            edges: payload.attributes()?,
+            faces: payload.attributes()?,
+        };
+
+        let face_location = if flags & FLAG_FACE_LOCATION == 0 {
+            None
+        } else {
+            let mut nodes = vec![];
+            for _ in 0..payload.u64()? {
+                nodes.push(match payload.byte()? {
+                    0 => BvhNode::Leaf {
+                        parent_index: payload.u64()?,
+                        shape_index: payload.u64()?,
+                    },
+                    1 => BvhNode::Node {
+                        parent_index: payload.u64()?,
+                        child_l_index: payload.u64()?,
+                        child_l_aabb: payload.aabb()?,
+                        child_r_index: payload.u64()?,
+                        child_r_aabb: payload.aabb()?,
+                    },
+                    tag => return Err(invalid(&format!("invalid node tag {tag}"))),
+                });
+            }
+            let mut triangles = vec![];
+            for _ in 0..payload.u64()? {
+                let corners = [payload.vector()?, payload.vector()?, payload.vector()?];
+                // Before version 2, every face was represented by the triangle of its first three corners.
+                let face_corners = if version < 2 {
+                    [0, 1, 2]
+                } else {
+                    [payload.u64()?, payload.u64()?, payload.u64()?]
+                };
+                triangles.push((corners, face_corners, payload.u64()?, payload.key()?));
+            }
+            if !is_hierarchy(&nodes, &triangles) {
+                return Err(invalid("invalid face location hierarchy"));
+            }
+            Some(FaceLocation::from_parts(nodes, triangles))
+        };
+
+        let vert_location = (flags & FLAG_VERT_LOCATION != 0).then(|| mesh.kdtree());
+
+        if !payload.0.is_empty() {
+            return Err(invalid("unexpected data at the end of the file"));
+        }
+
+        // Make sure that all references between elements are valid, before handing out the mesh.
+        let valid_edges = mesh.edge_ids().into_iter().all(|edge_id| {
+            mesh.edge_root.get(edge_id).is_some_and(|vert_id| mesh.verts.contains(vert_id))
+                && mesh.edge_face.get(edge_id).is_some_and(|face_id| mesh.faces.contains(face_id))
+                && mesh.edge_next.get(edge_id).is_some_and(|next_id| mesh.edges.contains(next_id))
+                && mesh.edge_twin.get(edge_id).is_some_and(|twin_id| mesh.edges.contains(twin_id))
+        });
+        let valid_verts = mesh
+            .vert_ids()
+            .into_iter()
+            .all(|vert_id| mesh.vert_repr.get(vert_id).is_some_and(|edge_id| mesh.edges.contains(edge_id)));
+        let valid_faces = mesh
+            .face_ids()
+            .into_iter()
+            .all(|face_id| mesh.face_repr.get(face_id).is_some_and(|edge_id| mesh.edges.contains(edge_id)));
+        if !(valid_edges && valid_verts && valid_faces) {
+            return Err(invalid("invalid references between elements"));
+        }
+
+        // The triangles of the face location should refer to existing faces, and to corners within those faces.
+        if let Some(face_location) = &face_location {
+            let degree = |face_id| {
+                let first = mesh.frep(face_id);
+                let mut edge_id = mesh.next(first);
+                let mut degree = 1;
+                while edge_id != first && degree <= mesh.nr_edges() {
+                    edge_id = mesh.next(edge_id);
+                    degree += 1;
+                }
+                degree
+            };
+            let (_, triangles) = face_location.parts();
+            if !triangles
+                .into_iter()
+                .all(|(_, face_corners, _, face_id)| mesh.faces.contains(face_id) && face_corners.iter().all(|&i| i < degree(face_id)))
+            {
+                return Err(invalid("invalid references in the face location"));
+            }
+        }
+
+        Ok(NativeFile {
+            mesh,
+            attributes,
+            face_location,
+            vert_location,
+        })
+    }
+}
+
+// Whether the nodes form a binary tree (from the root at index 0) whose leaves are the triangles, each in exactly one leaf that the triangle
+// refers back to, before any traversal of the hierarchy relies on it.
+fn is_hierarchy<M: Tag>(nodes: &[BvhNode<f64, 3>], triangles: &[TrianglePart<M>]) -> bool {
+    if nodes.is_empty() {
+        return triangles.is_empty();
+    }
+    let mut visited = vec![false; nodes.len()];
+    let mut leaves = 0;
+    let mut stack = vec![0];
+    while let Some(node_index) = stack.pop() {
+        if std::mem::replace(&mut visited[node_index], true) {
+            return false;
+        }
+        match nodes[node_index] {
+            BvhNode::Leaf { shape_index, .. } => {
+                if triangles.get(shape_index).is_none_or(|&(_, _, leaf_index, _)| leaf_index != node_index) {
+                    return false;
+                }
+                leaves += 1;
+            }
+            BvhNode::Node {
+                child_l_index, child_r_index, ..
+            } => {
+                for child_index in [child_l_index, child_r_index] {
+                    if nodes.get(child_index).is_none_or(|child| child.parent() != node_index) {
+                        return false;
+                    }
+                    stack.push(child_index);
+                }
+            }
+        }
+    }
+    leaves == triangles.len() && visited.into_iter().all(|visited| visited)
+}
+
+fn invalid(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+#[derive(Default)]
+struct Encoder(Vec<u8>);
+
+impl Encoder {
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+
+    fn u64(&mut self, value: usize) {
+        self.bytes(&(value as u64).to_le_bytes());
+    }
+
+    fn f64(&mut self, value: Float) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    fn vector(&mut self, value: Vector3D) {
+        for component in value.iter() {
+            self.f64(*component);
+        }
+    }
+
+    fn aabb(&mut self, aabb: &Aabb<f64, 3>) {
+        self.vector(aabb.min.coords);
+        self.vector(aabb.max.coords);
+    }
+
+    fn key<K, M>(&mut self, key: ids::Key<K, M>) {
+        self.bytes(&key.to_bits().to_le_bytes());
+    }
+
+    fn slots(&mut self, slots: &[usize]) {
+        self.u64(slots.len());
+        for &slot in slots {
+            self.u64(slot);
+        }
+    }
+
+    fn attributes<K: Copy + Eq + std::hash::Hash, M: Tag>(&mut self, attributes: &HashMap<String, Attribute<K, M>>) {
+        self.u64(attributes.len());
+        let mut names = attributes.keys().collect::<Vec<_>>();
+        names.sort();
+        for name in names {
+            self.u64(name.len());
+            self.bytes(name.as_bytes());
+            match &attributes[name] {
+                Attribute::Scalar(map) => {
+                    self.bytes(&[0]);
+                    self.u64(map.iter().count());
+                    for (key, &value) in map.iter() {
+                        self.key(key);
+                        self.f64(value);
+                    }
+                }
+                Attribute::Vector(map) => {
+                    self.bytes(&[1]);
+                    self.u64(map.iter().count());
+                    for (key, &value) in map.iter() {
+                        self.key(key);
+                        self.vector(value);
+                    }
+                }
+                Attribute::Integer(map) => {
+                    self.bytes(&[2]);
+                    self.u64(map.iter().count());
+                    for (key, &value) in map.iter() {
+                        self.key(key);
+                        self.bytes(&value.to_le_bytes());
+                    }
+                }
+            }
+        }
+    }
+}
+
+struct Decoder<'a>(&'a [u8]);
+
+impl Decoder<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], std::io::Error> {
+        if self.0.len() < N {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof));
+        }
+        let (bytes, rest) = self.0.split_at(N);
+        self.0 = rest;
+        Ok(bytes.try_into().unwrap())
+    }
+
+    fn byte(&mut self) -> Result<u8, std::io::Error> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    fn u64(&mut self) -> Result<usize, std::io::Error> {
+        usize::try_from(u64::from_le_bytes(self.take()?)).map_err(|_| invalid("number too large"))
+    }
+
+    fn f64(&mut self) -> Result<Float, std::io::Error> {
+        Ok(f64::from_le_bytes(self.take()?))
+    }
+
+    fn vector(&mut self) -> Result<Vector3D, std::io::Error> {
+        Ok(Vector3D::new(self.f64()?, self.f64()?, self.f64()?))
+    }
+
+    fn aabb(&mut self) -> Result<Aabb<f64, 3>, std::io::Error> {
+        let (min, max) = (self.vector()?, self.vector()?);
+        Ok(Aabb::with_bounds(min.into(), max.into()))
+    }
+
+    fn key<K, M>(&mut self) -> Result<ids::Key<K, M>, std::io::Error> {
+        Ok(ids::Key::from_bits(u64::from_le_bytes(self.take()?)))
+    }
+
+    // Free slots of the elements with the given keys. Before version 3, these were not stored, and every slot below the highest slot
+    // of the keys that has no key was free.
+    fn slots<K: Copy, M: Tag, V>(&mut self, version: u16, entries: &[(ids::Key<K, M>, V)]) -> Result<Vec<usize>, std::io::Error> {
+        if version < 3 {
+            return Ok(ids::free_slots(entries.iter().map(|&(key, _)| key)));
+        }
+        (0..self.u64()?).map(|_| self.u64()).collect()
+    }
+
+    fn attributes<K: Copy + Eq + std::hash::Hash, M: Tag>(&mut self) -> Result<HashMap<String, Attribute<K, M>>, std::io::Error> {
+        let mut attributes = HashMap::new();
+        for _ in 0..self.u64()? {
+            let length = self.u64()?;
+            if self.0.len() < length {
+                return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof));
+            }
+            let (name, rest) = self.0.split_at(length);
+            self.0 = rest;
+            let name = String::from_utf8(name.to_vec()).map_err(|_| invalid("attribute name is not valid UTF-8"))?;
+
+            let kind = self.byte()?;
+            let count = self.u64()?;
+            let attribute = match kind {
+                0 => {
+                    let mut map = ids::SecMap::new();
+                    for _ in 0..count {
+                        map.insert(self.key()?, self.f64()?);
+                    }
+                    Attribute::Scalar(map)
+                }
+                1 => {
+                    let mut map = ids::SecMap::new();
+                    for _ in 0..count {
+                        map.insert(self.key()?, self.vector()?);
+                    }
+                    Attribute::Vector(map)
+                }
+                2 => {
+                    let mut map = ids::SecMap::new();
+                    for _ in 0..count {
+                        map.insert(self.key()?, i64::from_le_bytes(self.take()?));
+                    }
+                    Attribute::Integer(map)
+                }
+                _ => return Err(invalid(&format!("invalid attribute kind {kind}"))),
+            };
+            attributes.insert(name, attribute);
+        }
+        Ok(attributes)
+    }
+}
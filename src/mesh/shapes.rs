@@ -0,0 +1,266 @@
+use crate::prelude::*;
+use itertools::Itertools;
+use std::collections::HashMap;
+
+// The five platonic solids.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Platonic {
+    Tetrahedron,
+    Hexahedron,
+    Octahedron,
+    Dodecahedron,
+    Icosahedron,
+}
+
+// Parametric primitives, all centered at the origin with outward facing faces. Apart from the box (with quads), the hexahedron (quads)
+// and the dodecahedron (pentagons), all primitives have triangle faces. Too small numbers of segments are raised to the smallest valid number.
+// There is no (subdivided) plane, since meshes cannot have a boundary.
+impl<M: Tag> Mesh<M> {
+    // Axis-aligned box with the given size along each axis, with 6 quad faces or 12 triangle faces.
+    #[must_use]
+    pub fn cuboid(size: Vector3D, triangulated: bool) -> Self {
+        let positions = [0., 1.]
+            .into_iter()
+            .cartesian_product([(0., 0.), (1., 0.), (1., 1.), (0., 1.)])
+            .map(|(z, (x, y))| Vector3D::new(x - 0.5, y - 0.5, z - 0.5).component_mul(&size))
+            .collect_vec();
+        let quads = [[0, 3, 2, 1], [4, 5, 6, 7], [0, 1, 5, 4], [1, 2, 6, 5], [2, 3, 7, 6], [3, 0, 4, 7]];
+        let faces = if triangulated {
+            quads.iter().flat_map(|&[a, b, c, d]| [vec![a, b, c], vec![a, c, d]]).collect_vec()
+        } else {
+            quads.iter().map(|quad| quad.to_vec()).collect_vec()
+        };
+        build(&faces, &positions)
+    }
+
+    // Sphere with `stacks` rings of faces from pole to pole (along the z-axis), and `slices` faces around each ring.
+    #[must_use]
+    pub fn uv_sphere(radius: Float, stacks: usize, slices: usize) -> Self {
+        let stacks = stacks.max(2);
+        let profile = (0..=stacks)
+            .map(|i| {
+                let theta = PI * i as Float / stacks as Float;
+                Vector2D::new(theta.sin(), theta.cos()) * radius
+            })
+            .collect_vec();
+        revolve(&profile, slices)
+    }
+
+    // Sphere from an icosahedron whose faces are subdivided into four, `level` times, and projected onto the sphere.
+    #[must_use]
+    pub fn icosphere(radius: Float, level: usize) -> Self {
+        let (mut faces, mut positions) = icosahedron();
+        for _ in 0..level {
+            let mut midpoints = HashMap::new();
+            let mut midpoint = |a: usize, b: usize, positions: &mut Vec<Vector3D>| {
+                *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                    positions.push((positions[a] + positions[b]) / 2.);
+                    positions.len() - 1
+                })
+            };
+            faces = faces
+                .iter()
+                .flat_map(|face| {
+                    let (a, b, c) = (face[0], face[1], face[2]);
+                    let (ab, bc, ca) = (midpoint(a, b, &mut positions), midpoint(b, c, &mut positions), midpoint(c, a, &mut positions));
+                    [vec![a, ab, ca], vec![b, bc, ab], vec![c, ca, bc], vec![ab, bc, ca]]
+                })
+                .collect_vec();
+        }
+        let positions = positions.iter().map(|p| p.normalize() * radius).collect_vec();
+        build(&faces, &positions)
+    }
+
+    // Torus around the z-axis, with the tube of radius `minor` at distance `major` from the axis.
+    // `major_segments` rings of faces around the axis, and `minor_segments` faces around each ring.
+    #[must_use]
+    pub fn torus(major: Float, minor: Float, major_segments: usize, minor_segments: usize) -> Self {
+        let minor_segments = minor_segments.max(3);
+        let profile = (0..minor_segments)
+            .map(|i| {
+                let theta = 2. * PI * i as Float / minor_segments as Float;
+                Vector2D::new(major + minor * theta.cos(), -minor * theta.sin())
+            })
+            .collect_vec();
+        revolve(&profile, major_segments)
+    }
+
+    // Cylinder along the z-axis, with `segments` faces around, capped with triangle fans.
+    #[must_use]
+    pub fn cylinder(radius: Float, height: Float, segments: usize) -> Self {
+        let profile = [(0., 0.5), (radius, 0.5), (radius, -0.5), (0., -0.5)].map(|(r, z)| Vector2D::new(r, z * height));
+        revolve(&profile, segments)
+    }
+
+    // Cylinder along the z-axis (of the given length) with hemispherical caps, with `segments` faces around, and `rings` rings of faces in each cap.
+    #[must_use]
+    pub fn capsule(radius: Float, length: Float, segments: usize, rings: usize) -> Self {
+        let rings = rings.max(1);
+        let profile = (0..=2 * rings + 1)
+            .map(|i| {
+                let (theta, z) = if i <= rings { (i, length / 2.) } else { (i - 1, -length / 2.) };
+                let theta = PI / 2. * theta as Float / rings as Float;
+                Vector2D::new(radius * theta.sin(), z + radius * theta.cos())
+            })
+            .collect_vec();
+        revolve(&profile, segments)
+    }
+
+    // Cone along the z-axis, with its base (capped with a triangle fan) at z = -height / 2 and its apex at z = height / 2, with `segments` faces around.
+    #[must_use]
+    pub fn cone(radius: Float, height: Float, segments: usize) -> Self {
+        let profile = [(0., 0.5), (radius, -0.5), (0., -0.5)].map(|(r, z)| Vector2D::new(r, z * height));
+        revolve(&profile, segments)
+    }
+
+    // Platonic solid with its vertices on the sphere of the given radius.
+    #[must_use]
+    pub fn platonic(solid: Platonic, radius: Float) -> Self {
+        let (faces, positions) = match solid {
+            Platonic::Tetrahedron => (
+                vec![vec![0, 1, 2], vec![0, 3, 1], vec![0, 2, 3], vec![1, 3, 2]],
+                vec![
+                    Vector3D::new(1., 1., 1.),
+                    Vector3D::new(1., -1., -1.),
+                    Vector3D::new(-1., 1., -1.),
+                    Vector3D::new(-1., -1., 1.),
+                ],
+            ),
+            Platonic::Hexahedron => return Self::cuboid(Vector3D::repeat(2. * radius / 3f64.sqrt()), false),
+            Platonic::Octahedron => {
+                let positions = (0..6).map(|i| Vector3D::ith(i % 3, if i < 3 { 1. } else { -1. })).collect_vec();
+                // One face for every octant, with a vertex on each of its axes.
+                let faces = (0..8)
+                    .map(|octant| (0..3).map(|axis| axis + 3 * (octant >> axis & 1)).collect_vec())
+                    .collect_vec();
+                (faces, positions)
+            }
+            Platonic::Dodecahedron => {
+                // The dual of the icosahedron, with a vertex for every face of the icosahedron, and a face for every vertex.
+                let (ico_faces, ico_positions) = icosahedron();
+                let positions = ico_faces
+                    .iter()
+                    .map(|face| face.iter().map(|&v| ico_positions[v]).sum::<Vector3D>() / 3.)
+                    .collect_vec();
+                let faces = (0..ico_positions.len())
+                    .map(|v| {
+                        let axis = ico_positions[v].normalize();
+                        let u = axis.cross(&Vector3D::ith(usize::from(axis.x.abs() > 0.5), 1.)).normalize();
+                        let w = axis.cross(&u);
+                        (0..ico_faces.len())
+                            .filter(|&f| ico_faces[f].contains(&v))
+                            .sorted_by(|&f, &g| {
+                                let angle = |f: usize| positions[f].dot(&w).atan2(positions[f].dot(&u));
+                                angle(f).total_cmp(&angle(g))
+                            })
+                            .collect_vec()
+                    })
+                    .collect_vec();
+                (faces, positions)
+            }
+            Platonic::Icosahedron => icosahedron(),
+        };
+        let positions = positions.iter().map(|p| p.normalize() * radius).collect_vec();
+        build(&outward(faces, &positions), &positions)
+    }
+}
+
+fn build<M: Tag>(faces: &[Vec<usize>], positions: &[Vector3D]) -> Mesh<M> {
+    Mesh::from(faces, positions).expect("primitives are closed 2-manifolds").0
+}
+
+// Surface of revolution around the z-axis of a profile of (radius, z) points, with `slices` faces around. The profile runs from top to bottom
+// (with the inside on its right), and either starts and ends on the axis (closed by a single vertex), or is a closed loop away from the axis.
+fn revolve<M: Tag>(profile: &[Vector2D], slices: usize) -> Mesh<M> {
+    let slices = slices.max(3);
+    let poles = profile[0].x == 0.;
+    let rings = if poles { &profile[1..profile.len() - 1] } else { profile };
+
+    let mut positions = vec![];
+    if poles {
+        positions.push(Vector3D::new(0., 0., profile[0].y));
+    }
+    let offset = positions.len();
+    for p in rings {
+        for j in 0..slices {
+            let phi = 2. * PI * j as Float / slices as Float;
+            positions.push(Vector3D::new(p.x * phi.cos(), p.x * phi.sin(), p.y));
+        }
+    }
+    let ring = |i: usize, j: usize| offset + (i % rings.len()) * slices + j % slices;
+
+    let mut faces = vec![];
+    let bands = if poles { rings.len() - 1 } else { rings.len() };
+    for j in 0..slices {
+        for i in 0..bands {
+            faces.push(vec![ring(i, j), ring(i + 1, j), ring(i + 1, j + 1)]);
+            faces.push(vec![ring(i, j), ring(i + 1, j + 1), ring(i, j + 1)]);
+        }
+    }
+    if poles {
+        positions.push(Vector3D::new(0., 0., profile[profile.len() - 1].y));
+        let (north, south) = (0, positions.len() - 1);
+        for j in 0..slices {
+            faces.push(vec![north, ring(0, j), ring(0, j + 1)]);
+            faces.push(vec![south, ring(rings.len() - 1, j + 1), ring(rings.len() - 1, j)]);
+        }
+    }
+    build(&faces, &positions)
+}
+
+// Regular icosahedron with its vertices at (0, ±1, ±φ) and its cyclic permutations.
+fn icosahedron() -> (Vec<Vec<usize>>, Vec<Vector3D>) {
+    let phi = (1. + 5f64.sqrt()) / 2.;
+    let positions = [
+        (-1., phi, 0.),
+        (1., phi, 0.),
+        (-1., -phi, 0.),
+        (1., -phi, 0.),
+        (0., -1., phi),
+        (0., 1., phi),
+        (0., -1., -phi),
+        (0., 1., -phi),
+        (phi, 0., -1.),
+        (phi, 0., 1.),
+        (-phi, 0., -1.),
+        (-phi, 0., 1.),
+    ]
+    .map(|(x, y, z)| Vector3D::new(x, y, z));
+    let faces = [
+        [0, 11, 5],
+        [0, 5, 1],
+        [0, 1, 7],
+        [0, 7, 10],
+        [0, 10, 11],
+        [1, 5, 9],
+        [5, 11, 4],
+        [11, 10, 2],
+        [10, 7, 6],
+        [7, 1, 8],
+        [3, 9, 4],
+        [3, 4, 2],
+        [3, 2, 6],
+        [3, 6, 8],
+        [3, 8, 9],
+        [4, 9, 5],
+        [2, 4, 11],
+        [6, 2, 10],
+        [8, 6, 7],
+        [9, 8, 1],
+    ];
+    (outward(faces.map(|face| face.to_vec()).to_vec(), &positions), positions.to_vec())
+}
+
+// Orient the faces of a convex polyhedron around the origin such that they face outward.
+fn outward(faces: Vec<Vec<usize>>, positions: &[Vector3D]) -> Vec<Vec<usize>> {
+    faces
+        .into_iter()
+        .map(|mut face| {
+            let (a, b, c) = (positions[face[0]], positions[face[1]], positions[face[2]]);
+            if (b - a).cross(&(c - a)).dot(&a) < 0. {
+                face.reverse();
+            }
+            face
+        })
+        .collect()
+}
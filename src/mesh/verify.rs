@@ -8,6 +8,9 @@ use core::panic;
 //      polygonal: Each face is a simple polygon (lies in a plane, no intersections).
 // These requirements will be true per construction.
 
+// Default tolerance on the planarity of faces: the largest distance of a corner to the best-fit plane, relative to the size of the face.
+pub const PLANARITY_TOLERANCE: Float = 1e-6;
+
 impl<M: Tag> Mesh<M> {
     // Asserts that all elements have their required properties set.
     // These assertions should all pass per construction.
@@ -79,10 +82,11 @@ impl<M: Tag> Mesh<M> {
             );
         }
         // this->next->...->next == this
-        const MAX_FACE_SIZE: usize = 10;
+        // (a face cannot have more edges than the mesh)
+        let max_face_size = self.nr_edges();
         for edge_id in self.edge_ids() {
             let mut next_id = edge_id;
-            for _ in 0..MAX_FACE_SIZE {
+            for _ in 0..max_face_size {
                 next_id = self.next(next_id);
                 if next_id == edge_id {
                     break;
@@ -97,7 +101,21 @@ impl<M: Tag> Mesh<M> {
     //     hutspot::graph::find_ccs(&self.vert_ids(), self.neighbor_function_primal()).len() == 1
     // }
 
+    // Checks that every face is a triangle.
+    pub fn is_triangular(&self) -> Result<(), MeshError<M>> {
+        match self.face_ids().into_iter().find(|&face_id| self.vertices(face_id).len() != 3) {
+            Some(face_id) => Err(MeshError::FaceNotTriangle(face_id)),
+            None => Ok(()),
+        }
+    }
+
+    // Checks that every face is a simple polygon, that is planar up to the default tolerance (see `Mesh::planarity`).
     pub fn is_polygonal(&self) -> Result<(), MeshError<M>> {
+        self.is_polygonal_within(PLANARITY_TOLERANCE)
+    }
+
+    // Checks that every face is a simple polygon, that is planar up to the given tolerance (see `Mesh::planarity`).
+    pub fn is_polygonal_within(&self, planarity_tolerance: Float) -> Result<(), MeshError<M>> {
         // Make sure the mesh is polygonal
         for face_id in self.face_ids() {
             let corners = self.vertices(face_id);
@@ -108,6 +126,9 @@ impl<M: Tag> Mesh<M> {
             }
 
             // Check that the face is planar
+            if self.planarity(face_id) > planarity_tolerance {
+                return Err(MeshError::FaceNotPlanar(face_id));
+            }
 
             let a = corners[0];
             for o in corners.into_iter().skip(1) {
@@ -117,16 +138,28 @@ impl<M: Tag> Mesh<M> {
             }
 
             // Check that the face is simple
-            for edge_a in self.edges(face_id) {
-                for edge_b in self.edges(face_id) {
-                    if edge_a == edge_b {
-                        continue;
-                    }
+            //      non-adjacent edges may not touch or cross each other (robustly, in the projection with the least distortion)
+            let axis = geom::dominant_axis(self.vector_area(face_id));
+            let edges = self.edges(face_id);
+            for (i, &edge_a) in edges.iter().enumerate() {
+                for &edge_b in edges.iter().skip(i + 1) {
                     let a_u = self.position(self.root(edge_a));
                     let a_v = self.position(self.toor(edge_a));
                     let b_u = self.position(self.root(edge_b));
                     let b_v = self.position(self.toor(edge_b));
-                    if geom::calculate_3d_lineseg_intersection(a_u, a_v, b_u, b_v).is_some() && a_u != b_u && a_u != b_v && a_v != b_u && a_v != b_v {
+                    if a_u == b_u || a_u == b_v || a_v == b_u || a_v == b_v {
+                        continue;
+                    }
+                    let (a_u, a_v, b_u, b_v) = (
+                        geom::drop_axis(a_u, axis),
+                        geom::drop_axis(a_v, axis),
+                        geom::drop_axis(b_u, axis),
+                        geom::drop_axis(b_v, axis),
+                    );
+                    let overlapping = geom::orient2d(a_u, a_v, b_u) == 0.
+                        && geom::orient2d(a_u, a_v, b_v) == 0.
+                        && (0..2).all(|k| a_u[k].min(a_v[k]) <= b_u[k].max(b_v[k]) && b_u[k].min(b_v[k]) <= a_u[k].max(a_v[k]));
+                    if overlapping || geom::calculate_2d_lineseg_intersection(a_u, a_v, b_u, b_v).is_some() {
                         return Err(MeshError::FaceNotSimple(face_id));
                     }
                 }
@@ -1,8 +1,50 @@
 use crate::prelude::*;
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
 use serde::{Deserialize, Serialize};
+#[cfg(any(feature = "stl", feature = "obj"))]
 use std::path::PathBuf;
 define_tag!(TestMesh);
 
+// Unit tetrahedron (corner at the origin), with outward facing faces.
+fn tetrahedron() -> Mesh<TestMesh> {
+    let faces = vec![vec![0, 2, 1], vec![0, 1, 3], vec![1, 2, 3], vec![0, 3, 2]];
+    let positions = [
+        Vector3D::new(0., 0., 0.),
+        Vector3D::new(1., 0., 0.),
+        Vector3D::new(0., 1., 0.),
+        Vector3D::new(0., 0., 1.),
+    ];
+    Mesh::from(&faces, &positions).unwrap().0
+}
+
+// Unit cube (corner at the origin) with quad faces, with outward facing faces.
+fn cube() -> Mesh<TestMesh> {
+    let faces = vec![
+        vec![0, 3, 2, 1],
+        vec![4, 5, 6, 7],
+        vec![0, 1, 5, 4],
+        vec![1, 2, 6, 5],
+        vec![2, 3, 7, 6],
+        vec![3, 0, 4, 7],
+    ];
+    let positions = [
+        Vector3D::new(0., 0., 0.),
+        Vector3D::new(1., 0., 0.),
+        Vector3D::new(1., 1., 0.),
+        Vector3D::new(0., 1., 0.),
+        Vector3D::new(0., 0., 1.),
+        Vector3D::new(1., 0., 1.),
+        Vector3D::new(1., 1., 1.),
+        Vector3D::new(0., 1., 1.),
+    ];
+    Mesh::from(&faces, &positions).unwrap().0
+}
+
+// Unit sphere (centered at the origin) as a triangulated UV-sphere, with outward facing faces.
+fn sphere(stacks: usize, slices: usize) -> Mesh<TestMesh> {
+    Mesh::uv_sphere(1., stacks, slices)
+}
+
 #[test]
 fn from_manual() {
     let faces = vec![vec![0, 2, 1], vec![0, 1, 3], vec![1, 2, 3], vec![0, 3, 2]];
@@ -19,6 +61,930 @@ fn from_manual() {
     }
 }
 
+#[test]
+fn robust_predicates() {
+    // Points very close to the line through (12, 12) and (24, 24), where a naive evaluation gives inconsistent signs.
+    let (a, b) = (Vector2D::new(12., 12.), Vector2D::new(24., 24.));
+    assert!(geom::orient2d(a, b, Vector2D::new(0.5, 0.5)) == 0.);
+    let mut x = 0.5_f64;
+    for _ in 0..64 {
+        x = f64::from_bits(x.to_bits() + 1);
+        assert!(geom::orient2d(a, b, Vector2D::new(x, 0.5)) < 0.);
+        assert!(geom::orient2d(b, a, Vector2D::new(x, 0.5)) > 0.);
+    }
+
+    let (o, x, y, z) = (Vector3D::zeros(), Vector3D::x(), Vector3D::y(), Vector3D::z());
+    assert!(geom::orient3d(o, x, y, z) > 0.);
+    assert!(geom::orient3d(o, y, x, z) < 0.);
+    assert!(geom::are_points_coplanar(o, x, y, x + y * 3.));
+    assert!(geom::incircle(Vector2D::x(), Vector2D::y(), -Vector2D::x(), Vector2D::new(0.5, 0.)) > 0.);
+    assert!(geom::incircle(-Vector2D::x(), Vector2D::y(), Vector2D::x(), Vector2D::new(2., 0.)) < 0.);
+    assert!(geom::insphere(x, y, z, -x, Vector3D::new(0.1, 0.1, 0.1)) > 0.);
+    assert!(geom::insphere(x, y, z, -x, Vector3D::new(0., -1., 0.)) == 0.);
+    assert!(geom::insphere(y, x, z, -x, Vector3D::new(0., 0., 2.)) < 0.);
+
+    // Segments touching at an endpoint, crossing, and (collinear) overlapping.
+    let intersection = geom::calculate_2d_lineseg_intersection(Vector2D::zeros(), Vector2D::new(2., 0.), Vector2D::new(1., 0.), Vector2D::new(1., 1.));
+    assert!(intersection == Some((Vector2D::new(1., 0.), geom::IntersectionType::Endpoint)));
+    let intersection = geom::calculate_2d_lineseg_intersection(Vector2D::zeros(), Vector2D::new(2., 2.), Vector2D::new(0., 2.), Vector2D::new(2., 0.));
+    assert!(intersection == Some((Vector2D::new(1., 1.), geom::IntersectionType::Proper)));
+    assert!(geom::calculate_2d_lineseg_intersection(Vector2D::zeros(), Vector2D::new(2., 0.), Vector2D::new(1., 0.), Vector2D::new(3., 0.)).is_none());
+
+    // Points in a triangle that is not axis-aligned, with coordinates far from the origin.
+    let offset = Vector3D::new(1e6, -1e6, 1e6);
+    let t = (offset, offset + Vector3D::new(1., 1., 0.), offset + Vector3D::new(0., 1., 1.));
+    assert!(geom::is_point_inside_triangle((t.0 + t.1 + t.2) / 3., t));
+    assert!(geom::is_point_inside_triangle(t.1, t));
+    assert!(!geom::is_point_inside_triangle(t.1 * 2. - t.0, t));
+    assert!(!geom::is_point_inside_triangle(t.0 + Vector3D::new(0.1, -0.1, 0.1), t));
+
+    assert!(cube().is_polygonal().is_ok());
+    assert!(tetrahedron().is_polygonal().is_ok());
+}
+
+#[test]
+fn concave_faces() {
+    // The hexahedron of `assets/hexahedron_concave.obj`, of which the front and back faces are concave quads.
+    let faces = vec![
+        vec![0, 1, 2, 3],
+        vec![7, 6, 5, 4],
+        vec![0, 4, 5, 1],
+        vec![1, 5, 6, 2],
+        vec![2, 6, 7, 3],
+        vec![3, 7, 4, 0],
+    ];
+    let positions = [
+        Vector3D::new(0., 0., 0.),
+        Vector3D::new(0.4, 0., 0.9),
+        Vector3D::new(1., 0., 1.),
+        Vector3D::new(0., 0., 1.),
+        Vector3D::new(0., 1., 0.),
+        Vector3D::new(0.4, 1., 0.9),
+        Vector3D::new(1., 1., 1.),
+        Vector3D::new(0., 1., 1.),
+    ];
+    let (douconel, _, face_map) = Mesh::<TestMesh>::from(&faces, &positions).unwrap();
+    assert!(douconel.is_polygonal().is_ok());
+
+    // The concave corner (0.4, 0, 0.9) would give a flipped normal if only the first three corners were used.
+    let front = *face_map.key(0).unwrap();
+    assert!((douconel.normal(front) - Vector3D::new(0., -1., 0.)).norm() < 1e-12);
+    assert!((douconel.size(front) - 0.25).abs() < 1e-12);
+    assert!(douconel.planarity(front) < 1e-12);
+
+    let projected = douconel.project(front);
+    let signed_area = projected.iter().zip(projected.iter().cycle().skip(1)).map(|(p, q)| p.perp(q)).sum::<Float>() / 2.;
+    assert!((signed_area - 0.25).abs() < 1e-12);
+
+    let (triangles, _) = douconel.triangulate().unwrap();
+    assert!(triangles.nr_faces() == 12);
+    assert!(triangles.face_ids().iter().all(|&face_id| triangles.vertices(face_id).len() == 3));
+    let area = |mesh: &Mesh<TestMesh>| mesh.face_ids().iter().map(|&face_id| mesh.size(face_id)).sum::<Float>();
+    assert!((area(&triangles) - area(&douconel)).abs() < 1e-12);
+    let mut normals = triangles.face_ids().into_iter().map(|face_id| triangles.normal(face_id));
+    assert!(normals.all(|normal| douconel.face_ids().iter().any(|&face_id| (douconel.normal(face_id) - normal).norm() < 1e-12)));
+
+    // Area of the unit square and the unit triangle.
+    let douconel = cube();
+    assert!(douconel.face_ids().iter().all(|&face_id| (douconel.size(face_id) - 1.).abs() < 1e-12));
+    let douconel = tetrahedron();
+    assert!(douconel.face_ids().iter().any(|&face_id| (douconel.size(face_id) - 0.5).abs() < 1e-12));
+
+    // Lifting a corner of a cube makes its faces non-planar.
+    let mut douconel = cube();
+    let vert_id = douconel
+        .vert_ids()
+        .into_iter()
+        .find(|&vert_id| douconel.position(vert_id) == Vector3D::new(1., 1., 1.))
+        .unwrap();
+    douconel.set_position(vert_id, Vector3D::new(1., 1., 1.2));
+    assert!(douconel.face_ids().iter().any(|&face_id| douconel.planarity(face_id) > 0.01));
+    assert!(matches!(douconel.is_polygonal(), Err(MeshError::FaceNotPlanar(_))));
+    assert!(douconel.is_polygonal_within(0.1).is_ok());
+
+    // Slivers are planar as well, both as triangles and as (nearly collinear) quads.
+    let positions = [
+        Vector3D::new(0., 0., 0.),
+        Vector3D::new(1., 0., 0.),
+        Vector3D::new(0.5, 1e-7, 1e-9),
+        Vector3D::new(0.25, 1e-7, 1e-9),
+    ];
+    let (slivers, _, _) = Mesh::<TestMesh>::from(&[vec![0, 1, 2], vec![2, 1, 0]], &positions).unwrap();
+    assert!(slivers.is_polygonal().is_ok());
+    let (slivers, _, _) = Mesh::<TestMesh>::from(&[vec![0, 1, 2, 3], vec![3, 2, 1, 0]], &positions).unwrap();
+    assert!(slivers.face_ids().iter().all(|&face_id| slivers.planarity(face_id) < 1e-12));
+    assert!(slivers.is_polygonal().is_ok());
+}
+
+#[test]
+fn vertex_normals() {
+    // The triangulation of the cube is irregular: corners have either one, two or three triangles per side.
+    let (douconel, _) = cube().triangulate().unwrap();
+    let center = Vector3D::new(0.5, 0.5, 0.5);
+    let mut uniform_deviates = false;
+    for vert_id in douconel.vert_ids() {
+        let expected = (douconel.position(vert_id) - center).normalize();
+        for weighting in [NormalWeighting::Angle, NormalWeighting::MaxFormula] {
+            assert!((douconel.vertex_normal(vert_id, weighting) - expected).norm() < 1e-12, "{weighting:?}");
+        }
+        assert!(douconel.vertex_normal(vert_id, NormalWeighting::Area).dot(&expected) > 0.5);
+        uniform_deviates |= (douconel.vertex_normal(vert_id, NormalWeighting::Uniform) - expected).norm() > 1e-3;
+    }
+    assert!(uniform_deviates);
+
+    let normals = douconel.compute_vertex_normals(NormalWeighting::default());
+    assert!(
+        douconel
+            .vert_ids()
+            .iter()
+            .all(|&vert_id| normals.get(vert_id) == Some(&douconel.normal(vert_id)))
+    );
+    // The default weighting is uniform, as before the weighting could be selected.
+    let equal = |normals: &ids::SecMap<VERT, TestMesh, Vector3D>, weighting| {
+        douconel
+            .vert_ids()
+            .iter()
+            .all(|&vert_id| normals.get(vert_id) == Some(&douconel.vertex_normal(vert_id, weighting)))
+    };
+    assert!(equal(&normals, NormalWeighting::Uniform));
+
+    // Normals cached in the attributes are used instead of the default ones, also after storing them in a native file.
+    let mut attributes = Attributes::default();
+    assert!(attributes.vertex_normals().is_none());
+    douconel.cache_vertex_normals(NormalWeighting::Angle, &mut attributes);
+    assert!(attributes.vertex_normals().is_some_and(|normals| equal(normals, NormalWeighting::Angle)));
+    let path = std::env::temp_dir().join("mehsh_normals.mehsh");
+    assert!(douconel.to_mehsh(&path, &attributes, NativeOptions::default()).is_ok());
+    let file = Mesh::<TestMesh>::from_mehsh(&path).unwrap();
+    assert!(file.attributes.vertex_normals().is_some_and(|normals| equal(normals, NormalWeighting::Angle)));
+}
+
+#[test]
+fn curvature() {
+    let douconel = sphere(24, 48);
+    let mixed_areas = douconel.mixed_areas().unwrap();
+    let total = douconel.vert_ids().iter().map(|&vert_id| mixed_areas.get_or_panic(vert_id)).sum::<Float>();
+    let area = douconel.face_ids().iter().map(|&face_id| douconel.size(face_id)).sum::<Float>();
+    assert!((total - area).abs() < 1e-9);
+
+    let mean = douconel.mean_curvature().unwrap();
+    let gaussian = douconel.gaussian_curvature().unwrap();
+    let principal = douconel.principal_curvatures().unwrap();
+    let shape_index = douconel.shape_index().unwrap();
+    let curvedness = douconel.curvedness().unwrap();
+    for vert_id in douconel.vert_ids() {
+        // Away from the poles, where the triangles are (nearly) degenerate.
+        if douconel.position(vert_id).z.abs() > 0.9 {
+            continue;
+        }
+        let normal = douconel.normal(vert_id);
+        let curvature = principal.get_or_panic(vert_id);
+        assert!((mean.get_or_panic(vert_id) - 1.).abs() < 0.05);
+        assert!((gaussian.get_or_panic(vert_id) - 1.).abs() < 0.05);
+        assert!((curvature.k1 - 1.).abs() < 0.05 && (curvature.k2 - 1.).abs() < 0.05);
+        assert!(curvature.d1.dot(&normal).abs() < 1e-9 && curvature.d2.dot(&curvature.d1).abs() < 1e-9);
+        assert!((shape_index.get_or_panic(vert_id) - 1.).abs() < 0.05);
+        assert!((curvedness.get_or_panic(vert_id) - 1.).abs() < 0.05);
+    }
+
+    // A cylinder-like ridge, a saddle and a flat point.
+    let curvature = |k1, k2| PrincipalCurvature { k1, k2, ..Default::default() };
+    assert!((curvature(1., 0.).shape_index() - 0.5).abs() < 1e-12);
+    assert!(curvature(1., -1.).shape_index().abs() < 1e-12);
+    assert!(curvature(0., 0.).shape_index().is_nan());
+
+    assert!(matches!(cube().mean_curvature(), Err(MeshError::FaceNotTriangle(_))));
+}
+
+#[test]
+fn sparse_operators() {
+    let douconel = sphere(12, 24);
+    let (n, area) = (
+        douconel.nr_verts(),
+        douconel.face_ids().iter().map(|&face_id| douconel.size(face_id)).sum::<Float>(),
+    );
+
+    // The Laplacian is symmetric and vanishes on constant functions.
+    let laplacian = douconel.cotan_laplacian().unwrap();
+    assert_eq!((laplacian.nrows, laplacian.ncols), (n, n));
+    assert!(laplacian.iter().all(|(i, j, value)| (value - laplacian.get(j, i)).abs() < 1e-12));
+    assert!(laplacian.mul_vec(&vec![1.; n]).iter().all(|value| value.abs() < 1e-9));
+
+    // Every mass matrix integrates the constant function to the total area.
+    for kind in [MassMatrix::Lumped, MassMatrix::Voronoi, MassMatrix::Full] {
+        let mass = douconel.mass_matrix(kind).unwrap();
+        assert!((mass.mul_vec(&vec![1.; n]).iter().sum::<Float>() - area).abs() < 1e-9, "{kind:?}");
+    }
+
+    // The gradient of a linear function is exact, and the divergence of the gradient is the (negative) Laplacian.
+    let vert_map = douconel.vert_index_map();
+    let heights = (0..n).map(|i| douconel.position(*vert_map.key(i).unwrap()).z).collect::<Vec<_>>();
+    let gradient = douconel.gradient().unwrap();
+    for (f, face_id) in douconel.face_ids().into_iter().enumerate() {
+        let expected = Vector3D::z() - douconel.normal(face_id) * douconel.normal(face_id).z;
+        let values = gradient.mul_vec(&heights);
+        assert!((Vector3D::new(values[3 * f], values[3 * f + 1], values[3 * f + 2]) - expected).norm() < 1e-9);
+    }
+    let divergence = douconel.divergence().unwrap();
+    let composed = divergence.mul(&gradient);
+    assert!(laplacian.iter().all(|(i, j, value)| (value + composed.get(i, j)).abs() < 1e-9));
+    assert_eq!(composed.nnz(), laplacian.nnz());
+
+    // Screened Poisson equation (-Δ + 1) u = 3z, with solution u = z (as -Δz = 2z on the unit sphere).
+    let mass = douconel.mass_matrix(MassMatrix::Lumped).unwrap();
+    let rhs = mass.mul_vec(&heights.iter().map(|z| 3. * z).collect::<Vec<_>>());
+    let solution = laplacian.add(&mass).conjugate_gradient(&rhs, 1e-12, 1000).unwrap();
+    assert!(solution.iter().zip(&heights).all(|(u, z)| (u - z).abs() < 0.05));
+
+    #[cfg(feature = "nalgebra-sparse")]
+    {
+        let matrix = nalgebra_sparse::CsrMatrix::from(&laplacian);
+        assert_eq!(matrix.nnz(), laplacian.nnz());
+        assert!(matrix.triplet_iter().all(|(i, j, &value)| value == laplacian.get(i, j)));
+    }
+
+    assert!(matches!(cube().cotan_laplacian(), Err(MeshError::FaceNotTriangle(_))));
+}
+
+#[test]
+fn integral_properties() {
+    let douconel = cube();
+    assert!((douconel.surface_area() - 6.).abs() < 1e-12);
+    assert!((douconel.volume() - 1.).abs() < 1e-12);
+    assert!((douconel.area_centroid() - Vector3D::repeat(0.5)).norm() < 1e-12);
+    assert!((douconel.volume_centroid() - Vector3D::repeat(0.5)).norm() < 1e-12);
+    assert!((douconel.inertia_tensor() - nalgebra::Matrix3::identity() / 6.).norm() < 1e-12);
+
+    let douconel = tetrahedron();
+    assert!((douconel.volume() - 1. / 6.).abs() < 1e-12);
+    assert!((douconel.volume_centroid() - Vector3D::repeat(0.25)).norm() < 1e-12);
+    assert!((douconel.surface_area() - (1.5 + Float::sqrt(3.) / 2.)).abs() < 1e-12);
+
+    // A 1 x 2 x 4 box, rotated and translated: the smallest moment is around the longest side.
+    let rotation = nalgebra::Rotation3::from_euler_angles(0.3, -0.7, 1.1);
+    let offset = Vector3D::new(10., -5., 3.);
+    let mut douconel = cube();
+    for vert_id in douconel.vert_ids() {
+        let p = douconel.position(vert_id);
+        douconel.set_position(vert_id, rotation * Vector3D::new(p.x, 2. * p.y, 4. * p.z) + offset);
+    }
+    assert!((douconel.volume() - 8.).abs() < 1e-9);
+    assert!((douconel.volume_centroid() - (rotation * Vector3D::new(0.5, 1., 2.) + offset)).norm() < 1e-9);
+    let [(smallest, axis), (middle, _), (largest, _)] = douconel.principal_axes();
+    assert!((smallest - 8. * 5. / 12.).abs() < 1e-9 && (middle - 8. * 17. / 12.).abs() < 1e-9 && (largest - 8. * 20. / 12.).abs() < 1e-9);
+    assert!((axis.dot(&(rotation * Vector3D::z())).abs() - 1.).abs() < 1e-9);
+}
+
+#[test]
+fn ray_queries() {
+    let douconel = cube();
+    let bvh = douconel.bvh();
+    let (origin, up) = (Vector3D::new(0.5, 0.5, -1.), Vector3D::z());
+
+    // The ray passes through the diagonals of the (triangulated) bottom and top faces, but hits each of them once.
+    let hit = bvh.ray_intersect(origin, up).unwrap();
+    assert!((hit.t - 1.).abs() < 1e-12);
+    assert!(douconel.normal(hit.face).z < -0.5);
+    let corners = douconel.vertices(hit.face);
+    let position = (0..3)
+        .map(|i| douconel.position(corners[hit.corners[i]]) * hit.barycentric[i])
+        .sum::<Vector3D>();
+    assert!((position - Vector3D::new(0.5, 0.5, 0.)).norm() < 1e-12);
+    let hits = bvh.ray_intersect_all(origin, up);
+    assert_eq!(hits.iter().map(|hit| hit.t).collect::<Vec<_>>(), vec![1., 2.]);
+    assert!(bvh.occluded(origin, up, 1.5));
+    assert!(!bvh.occluded(origin, up, 0.5));
+    assert!(bvh.ray_intersect(origin, -up).is_none());
+    assert!(bvh.ray_intersect(origin, Vector3D::zeros()).is_none());
+
+    // Watertight: rays from the center through vertices and edge midpoints never slip through.
+    let douconel = sphere(8, 16);
+    let bvh = douconel.bvh();
+    for edge_id in douconel.edge_ids() {
+        for target in [douconel.position(douconel.root(edge_id)), douconel.midpoint_offset(edge_id, 0.5)] {
+            let hits = bvh.ray_intersect_all(Vector3D::zeros(), target);
+            assert!(!hits.is_empty());
+            assert!(hits.iter().all(|hit| (hit.t - 1.).abs() < 1e-9));
+        }
+    }
+}
+
+#[test]
+fn closest_points() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let douconel = cube();
+    let bvh = douconel.bvh();
+
+    // Above the second half of the top face (not covered by the triangle of its first three corners).
+    let surface_point = bvh.closest_point(Vector3D::new(0.1, 0.9, 1.5)).unwrap();
+    assert!((surface_point.distance - 0.5).abs() < 1e-12);
+    assert!((surface_point.position - Vector3D::new(0.1, 0.9, 1.)).norm() < 1e-12);
+    let corners = douconel.vertices(surface_point.face);
+    let position = (0..3)
+        .map(|i| douconel.position(corners[surface_point.corners[i]]) * surface_point.barycentric[i])
+        .sum::<Vector3D>();
+    assert!((position - surface_point.position).norm() < 1e-12);
+
+    // Closest to a corner and to an edge, and a point on the surface.
+    let surface_points = bvh.closest_points(&[Vector3D::new(2., 2., 2.), Vector3D::new(2., 0.5, -1.), Vector3D::new(0.3, 0., 0.6)]);
+    let distances = surface_points.iter().map(|surface_point| surface_point.unwrap().distance).collect::<Vec<_>>();
+    assert!((distances[0] - Float::sqrt(3.)).abs() < 1e-12 && (distances[1] - Float::sqrt(2.)).abs() < 1e-12 && distances[2] < 1e-12);
+
+    // Compare with brute force.
+    let douconel = sphere(8, 16);
+    let bvh = douconel.bvh();
+    for _ in 0..100 {
+        let point = Vector3D::new(rng.random(), rng.random(), rng.random()) * 4. - Vector3D::repeat(2.);
+        let expected = douconel
+            .face_ids()
+            .into_iter()
+            .map(|face_id| {
+                let corners = douconel
+                    .vertices(face_id)
+                    .into_iter()
+                    .map(|vert_id| douconel.position(vert_id))
+                    .collect::<Vec<_>>();
+                geom::distance_to_triangle(point, (corners[0], corners[1], corners[2]))
+            })
+            .fold(INF, Float::min);
+        let surface_point = bvh.closest_point(point).unwrap();
+        assert!((surface_point.distance - expected).abs() < 1e-12);
+        assert!(((surface_point.position - point).norm() - expected).abs() < 1e-12);
+    }
+
+    assert!(FaceLocation::<TestMesh>::default().closest_point(Vector3D::zeros()).is_none());
+}
+
+#[test]
+fn vertex_queries() {
+    let douconel = cube();
+    let mut kdtree = douconel.kdtree();
+    let origin = douconel
+        .vert_ids()
+        .into_iter()
+        .find(|&vert_id| douconel.position(vert_id) == Vector3D::zeros())
+        .unwrap();
+
+    let (d, vert_id) = kdtree.nearest(&[-0.1, -0.1, -0.1]).unwrap();
+    assert!(vert_id == origin && (d - 0.03).abs() < 1e-12);
+    let nearest = kdtree.k_nearest(&[0., 0., 0.], 4);
+    assert_eq!(nearest.iter().map(|&(d, _)| d).collect::<Vec<_>>(), vec![0., 1., 1., 1.]);
+    assert_eq!(kdtree.within_radius(&[0., 0., 0.], 1.).len(), 4);
+    // Distances are squared, and so is the radius.
+    let within = kdtree.within_radius(&[0., 0., 0.], 2.);
+    assert!(within.len() == 7 && within.iter().filter(|&&(d, _)| d == 2.).count() == 3);
+    let (d, vert_id) = kdtree.nearest_excluding(&[0., 0., 0.], &[origin].into()).unwrap();
+    assert!(vert_id != origin && d == 1.);
+
+    // Removing and moving vertices, including onto the position of another vertex.
+    assert!(kdtree.remove(origin) && !kdtree.remove(origin));
+    assert!(kdtree.nearest(&[0., 0., 0.]).unwrap().0 == 1.);
+    assert!(kdtree.insert(origin, [1., 1., 1.]) && kdtree.insert(origin, [2., 2., 2.]));
+    assert!(kdtree.nearest(&[2., 2., 2.]).unwrap() == (0., origin));
+    assert_eq!(kdtree.within_radius(&[1., 1., 1.], 0.).len(), 1);
+    assert_eq!(kdtree.len(), 8);
+    // Non-finite positions are not indexed.
+    assert!(!kdtree.insert(origin, [Float::NAN, 0., 0.]) && !kdtree.insert(origin, [INF, 0., 0.]));
+    assert!(kdtree.len() == 7 && !kdtree.contains(origin) && kdtree.nearest(&[2., 2., 2.]).unwrap().1 != origin);
+
+    // Many edits (such that the tree is rebuilt), until it is empty.
+    for i in 0..200 {
+        kdtree.insert(origin, [i as Float, 0., 0.]);
+    }
+    assert!(kdtree.nearest(&[0., 0., 0.]).unwrap().1 != origin);
+    assert!(kdtree.nearest(&[199., 0., 0.]).unwrap() == (0., origin));
+    for vert_id in douconel.vert_ids() {
+        kdtree.remove(vert_id);
+    }
+    assert!(kdtree.is_empty() && kdtree.nearest(&[0., 0., 0.]).is_none() && kdtree.k_nearest(&[0., 0., 0.], 3).is_empty());
+    assert!(VertLocation::<TestMesh>::default().nearest(&[0., 0., 0.]).is_none());
+}
+
+#[test]
+fn winding_numbers() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let bvh = cube().bvh();
+    assert!((bvh.winding_number(Vector3D::repeat(0.5)) - 1.).abs() < 1e-9);
+    assert!((bvh.winding_number(Vector3D::new(0.5, 0.5, 0.001)) - 1.).abs() < 1e-9);
+    assert!(bvh.winding_number(Vector3D::new(0.5, 0.5, -0.001)).abs() < 1e-9);
+    assert!(bvh.winding_number(Vector3D::repeat(10.)).abs() < 1e-9);
+
+    let bvh = sphere(16, 32).bvh();
+    for _ in 0..200 {
+        let point = Vector3D::new(rng.random(), rng.random(), rng.random()) * 4. - Vector3D::repeat(2.);
+        let exact = bvh.winding_number_with_accuracy(point, INF);
+        assert!((bvh.winding_number(point) - exact).abs() < 0.02);
+        if point.norm() < 0.95 || point.norm() > 1.05 {
+            assert_eq!(bvh.contains(point), point.norm() < 1.);
+        }
+    }
+    assert!(FaceLocation::<TestMesh>::default().winding_number(Vector3D::zeros()) == 0.);
+}
+
+#[test]
+fn intersections() {
+    assert!(cube().self_intersections().is_empty());
+    assert!(sphere(16, 32).self_intersections().is_empty() && sphere(16, 32).self_intersection_curves().is_empty());
+
+    // Two cubes overlapping in [0.5, 1]^3 intersect in a single closed curve.
+    let mut shifted = cube();
+    for vert_id in shifted.vert_ids() {
+        shifted.set_position(vert_id, shifted.position(vert_id) + Vector3D::repeat(0.5));
+    }
+    assert!(!cube().intersections_with(&shifted).is_empty());
+    let curves = cube().intersection_curves_with(&shifted);
+    assert!(curves.len() == 1 && curves[0].len() > 3 && curves[0].first() == curves[0].last(), "{curves:?}");
+    for point in &curves[0] {
+        assert!(point.iter().all(|&x| (0.5 - 1e-9..=1. + 1e-9).contains(&x)));
+    }
+    for vert_id in shifted.vert_ids() {
+        shifted.set_position(vert_id, shifted.position(vert_id) + Vector3D::repeat(2.));
+    }
+    assert!(cube().intersections_with(&shifted).is_empty());
+
+    // Pushing the north pole of a sphere through its south pole.
+    let mut sphere = sphere(16, 32);
+    let pole = sphere.vert_ids().into_iter().find(|&vert_id| sphere.position(vert_id).z > 0.99).unwrap();
+    sphere.set_position(pole, Vector3D::new(0., 0., -1.5));
+    let pairs = sphere.self_intersections();
+    assert!(!pairs.is_empty());
+    assert!(
+        pairs
+            .iter()
+            .all(|&(a, b)| sphere.vertices(a).contains(&pole) || sphere.vertices(b).contains(&pole))
+    );
+    assert!(!sphere.self_intersection_curves().is_empty());
+}
+
+#[test]
+fn booleans() {
+    let translated = |mut mesh: Mesh<TestMesh>, offset: Vector3D| {
+        for vert_id in mesh.vert_ids() {
+            mesh.set_position(vert_id, mesh.position(vert_id) + offset);
+        }
+        mesh
+    };
+
+    // Cubes overlapping in a corner, in a slab (with coplanar faces), and not at all.
+    for (offset, overlap) in [(Vector3D::repeat(0.5), 0.125), (Vector3D::new(0.5, 0., 0.), 0.5), (Vector3D::repeat(2.), 0.)] {
+        let (a, b) = (cube(), translated(cube(), offset));
+        for (operation, volume) in [
+            (BooleanOperation::Union, 2. - overlap),
+            (BooleanOperation::Intersection, overlap),
+            (BooleanOperation::Difference, 1. - overlap),
+        ] {
+            let (result, origins) = a.boolean(&b, operation).unwrap();
+            assert!((result.volume() - volume).abs() < 1e-9, "{operation:?} {offset:?}: {}", result.volume());
+            assert!(result.self_intersections().is_empty());
+            for face_id in result.face_ids() {
+                let (input, source) = match origins.get_or_panic(face_id) {
+                    FaceOrigin::First(source) => (&a, *source),
+                    FaceOrigin::Second(source) => (&b, *source),
+                };
+                let alignment = result.normal(face_id).dot(&input.normal(source));
+                let flipped = operation == BooleanOperation::Difference && matches!(origins.get_or_panic(face_id), FaceOrigin::Second(_));
+                assert!((alignment - if flipped { -1. } else { 1. }).abs() < 1e-9);
+            }
+        }
+    }
+    let (union, _) = cube().union(&cube()).unwrap();
+    assert!(union.nr_faces() == 6 && (union.volume() - 1.).abs() < 1e-9);
+
+    // Inclusion-exclusion, for curved meshes.
+    let (a, b) = (sphere(12, 24), translated(sphere(10, 20), Vector3D::new(0.7, 0.2, 0.1)));
+    let (union, _) = a.union(&b).unwrap();
+    let (intersection, _) = a.intersection(&b).unwrap();
+    let (difference, _) = a.difference(&b).unwrap();
+    assert!(intersection.volume() > 0.1);
+    assert!((union.volume() - (a.volume() + b.volume() - intersection.volume())).abs() < 1e-9);
+    assert!((difference.volume() - (a.volume() - intersection.volume())).abs() < 1e-9);
+    assert!([union, intersection, difference].iter().all(|mesh| mesh.is_polygonal().is_ok()));
+}
+
+#[test]
+fn signed_distances() {
+    let bbox = (Vector3D::repeat(-0.5), Vector3D::repeat(1.5));
+    let grid = cube().sdf_grid(bbox, [5, 9, 3]);
+    assert!(grid.len() == 5 * 9 * 3 && grid.spacing == Vector3D::new(0.5, 0.25, 1.));
+    for index in 0..grid.len() {
+        let coordinates = grid.coordinates(index);
+        assert!(grid.index(coordinates) == index);
+        // Signed distance of the unit cube.
+        let d = (grid.position(coordinates) - Vector3D::repeat(0.5)).abs() - Vector3D::repeat(0.5);
+        let expected = d.map(|x| x.max(0.)).norm() + d.max().min(0.);
+        assert!((grid.get(coordinates).unwrap() - expected).abs() < 1e-9);
+    }
+    assert!(grid.get([5, 0, 0]).is_none() && *grid.get([4, 8, 2]).unwrap() > 0.);
+
+    let bvh = sphere(16, 32).bvh();
+    assert!(bvh.signed_distance(Vector3D::zeros()) < -0.99 && bvh.signed_distance(Vector3D::repeat(2.)) > 2.4);
+    assert!(FaceLocation::<TestMesh>::default().signed_distance(Vector3D::zeros()) == INF);
+}
+
+#[test]
+fn isosurfaces() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let bbox = (Vector3D::repeat(-1.), Vector3D::repeat(1.));
+
+    // Sphere of radius 0.8.
+    let sphere = Mesh::<TestMesh>::from_implicit(|p| p.norm() - 0.8, bbox, [20, 20, 20], 0., Isosurface::MarchingCubes).unwrap();
+    assert!(sphere.nr_verts() as i64 - (sphere.nr_edges() / 2) as i64 + sphere.nr_faces() as i64 == 2);
+    assert!((sphere.volume() - 4. / 3. * PI * 0.8_f64.powi(3)).abs() < 0.05);
+    assert!(
+        sphere
+            .vert_ids()
+            .into_iter()
+            .all(|vert_id| (sphere.position(vert_id).norm() - 0.8).abs() < 0.01)
+    );
+
+    // Random fields have many ambiguous faces, but still give closed meshes, and the field is capped at the boundary of the grid.
+    for _ in 0..10 {
+        let mut grid = Grid::from_fn(bbox, [6, 5, 4], |_| 0.);
+        grid.values.iter_mut().for_each(|value| *value = rng.random::<f64>() - 0.5);
+        let mesh = Mesh::<TestMesh>::from_grid(&grid, 0., Isosurface::MarchingCubes).unwrap();
+        assert!(mesh.volume() > 0.);
+    }
+    let grid = Grid::from_fn(bbox, [3, 3, 3], |_| -1.);
+    let mesh = Mesh::<TestMesh>::from_grid(&grid, 0., Isosurface::MarchingCubes).unwrap();
+    assert!(
+        mesh.volume() > 8.
+            && mesh
+                .vert_ids()
+                .into_iter()
+                .all(|vert_id| (mesh.position(vert_id).abs().max() - 1.5).abs() < 1e-9)
+    );
+
+    // Dual contouring reproduces the sharp edges and corners of a box.
+    let cube = |p: Vector3D| (p.abs() - Vector3D::repeat(0.5)).max();
+    let mesh = Mesh::<TestMesh>::from_implicit(cube, bbox, [16, 16, 16], 0., Isosurface::DualContouring).unwrap();
+    assert!((mesh.volume() - 1.).abs() < 1e-6, "{}", mesh.volume());
+    assert!(
+        mesh.vert_ids()
+            .into_iter()
+            .any(|vert_id| (mesh.position(vert_id) - Vector3D::repeat(0.5)).norm() < 1e-6)
+    );
+    let grid = Grid::from_fn(bbox, [16, 16, 16], cube);
+    let mesh = Mesh::<TestMesh>::from_grid(&grid, 0., Isosurface::DualContouring).unwrap();
+    assert!((mesh.volume() - 1.).abs() < 0.05);
+    assert!(Mesh::<TestMesh>::from_grid(&Grid::from_fn(bbox, [1, 3, 3], cube), 0., Isosurface::MarchingCubes).is_err());
+}
+
+#[test]
+fn primitives() {
+    let euler = |mesh: &Mesh<TestMesh>| mesh.nr_verts() as i64 - (mesh.nr_edges() / 2) as i64 + mesh.nr_faces() as i64;
+    let close = |a: Float, b: Float, tolerance: Float| (a - b).abs() <= tolerance * b.abs().max(1.);
+
+    for triangulated in [false, true] {
+        let cuboid = Mesh::<TestMesh>::cuboid(Vector3D::new(2., 3., 4.), triangulated);
+        assert!(cuboid.nr_faces() == if triangulated { 12 } else { 6 } && euler(&cuboid) == 2);
+        assert!(close(cuboid.volume(), 24., 1e-12) && close(cuboid.surface_area(), 52., 1e-12));
+        assert!(cuboid.is_polygonal().is_ok());
+    }
+
+    // Platonic solids with circumradius 1, with their number of faces and volume.
+    let (a, b) = (4. / 6f64.sqrt(), 1. / (2. * PI / 5.).sin());
+    let c = 4. / (3f64.sqrt() * (1. + 5f64.sqrt()));
+    for (solid, nr_faces, volume) in [
+        (Platonic::Tetrahedron, 4, a.powi(3) / (6. * 2f64.sqrt())),
+        (Platonic::Hexahedron, 6, (2. / 3f64.sqrt()).powi(3)),
+        (Platonic::Octahedron, 8, 4. / 3.),
+        (Platonic::Dodecahedron, 12, (15. + 7. * 5f64.sqrt()) / 4. * c.powi(3)),
+        (Platonic::Icosahedron, 20, 5. / 12. * (3. + 5f64.sqrt()) * b.powi(3)),
+    ] {
+        let mesh = Mesh::<TestMesh>::platonic(solid, 1.);
+        assert!(mesh.nr_faces() == nr_faces && euler(&mesh) == 2, "{solid:?}");
+        assert!(close(mesh.volume(), volume, 1e-9), "{solid:?}: {} != {volume}", mesh.volume());
+        assert!(mesh.vert_ids().into_iter().all(|vert_id| close(mesh.position(vert_id).norm(), 1., 1e-12)));
+        assert!(mesh.is_polygonal().is_ok() && mesh.self_intersections().is_empty());
+    }
+
+    // Polygonal cylinders and cones have exact volumes.
+    let base = |r: Float, n: usize| n as Float / 2. * (2. * PI / n as Float).sin() * r * r;
+    let cylinder = Mesh::<TestMesh>::cylinder(0.5, 2., 16);
+    assert!(euler(&cylinder) == 2 && close(cylinder.volume(), base(0.5, 16) * 2., 1e-12));
+    let cone = Mesh::<TestMesh>::cone(1., 3., 7);
+    assert!(euler(&cone) == 2 && close(cone.volume(), base(1., 7), 1e-12));
+    assert!(Mesh::<TestMesh>::cone(1., 1., 1).nr_faces() == 6);
+
+    // Smooth surfaces converge to their volume.
+    let uv_sphere = Mesh::<TestMesh>::uv_sphere(2., 32, 64);
+    assert!(euler(&uv_sphere) == 2 && close(uv_sphere.volume(), 32. / 3. * PI, 0.01));
+    let icosphere = Mesh::<TestMesh>::icosphere(2., 3);
+    assert!(icosphere.nr_faces() == 20 * 4usize.pow(3) && euler(&icosphere) == 2);
+    assert!(close(icosphere.volume(), 32. / 3. * PI, 0.02));
+    let torus = Mesh::<TestMesh>::torus(2., 0.5, 64, 32);
+    assert!(euler(&torus) == 0 && close(torus.volume(), 2. * PI * PI * 2. * 0.25, 0.01));
+    assert!(torus.self_intersections().is_empty());
+    let capsule = Mesh::<TestMesh>::capsule(0.5, 1., 32, 8);
+    assert!(euler(&capsule) == 2 && close(capsule.volume(), PI * 0.25 + 4. / 3. * PI * 0.125, 0.02));
+}
+
+#[test]
+fn convex_hulls() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let euler = |mesh: &Mesh<TestMesh>| mesh.nr_verts() as i64 - (mesh.nr_edges() / 2) as i64 + mesh.nr_faces() as i64;
+
+    // Corners of the unit cube, with points inside, and on its faces and edges (which are not corners of the hull).
+    let mut points = cube().vert_ids().into_iter().map(|vert_id| cube().position(vert_id)).collect::<Vec<_>>();
+    points.extend([Vector3D::new(0.5, 0.5, 0.), Vector3D::new(0.5, 0., 0.), Vector3D::new(1., 0.25, 1.), points[3]]);
+    points.extend((0..100).map(|_| Vector3D::new(rng.random(), rng.random(), rng.random())));
+    let (hull, input) = Mesh::<TestMesh>::from_convex_hull(&points, false).unwrap();
+    assert!(hull.nr_verts() == 8 && hull.nr_faces() == 12 && hull.is_triangular().is_ok());
+    assert!(hull.vert_ids().into_iter().all(|vert_id| {
+        let position = points[*input.id(&vert_id).unwrap()];
+        position == hull.position(vert_id) && position.iter().all(|&x| x == 0. || x == 1.)
+    }));
+    assert!((hull.volume() - 1.).abs() < 1e-12);
+    let (merged, _) = Mesh::<TestMesh>::from_convex_hull(&points, true).unwrap();
+    assert!(merged.nr_faces() == 6 && merged.face_ids().into_iter().all(|face_id| merged.vertices(face_id).len() == 4));
+    assert!((merged.volume() - 1.).abs() < 1e-12 && merged.is_polygonal().is_ok());
+    // The same holds in any order, also when the points on faces and edges come before the corners.
+    let mut grid = (0..27)
+        .map(|i| Vector3D::new((i % 3) as Float, (i / 3 % 3) as Float, (i / 9) as Float) / 2.)
+        .collect::<Vec<_>>();
+    for _ in 0..100 {
+        grid.shuffle(&mut rng);
+        let (hull, _) = Mesh::<TestMesh>::from_convex_hull(&grid, false).unwrap();
+        assert!(hull.nr_verts() == 8 && hull.nr_faces() == 12 && (hull.volume() - 1.).abs() < 1e-12);
+    }
+
+    // Random points, all of which are inside (or on) the hull.
+    let points = (0..500)
+        .map(|_| Vector3D::new(rng.random(), rng.random(), rng.random()) * 2. - Vector3D::repeat(1.))
+        .collect::<Vec<_>>();
+    let (hull, _) = Mesh::<TestMesh>::from_convex_hull(&points, false).unwrap();
+    assert!(euler(&hull) == 2 && hull.self_intersections().is_empty());
+    for face_id in hull.face_ids() {
+        let corners = hull.vertices(face_id).into_iter().map(|vert_id| hull.position(vert_id)).collect::<Vec<_>>();
+        assert!(points.iter().all(|&p| geom::orient3d(corners[0], corners[1], corners[2], p) <= 0.));
+    }
+
+    // The hull of a convex mesh is the mesh itself.
+    let sphere = sphere(8, 16);
+    let (hull, origins) = sphere.convex_hull(false).unwrap();
+    assert!(hull.nr_verts() == sphere.nr_verts() && (hull.volume() - sphere.volume()).abs() < 1e-12);
+    assert!(
+        hull.vert_ids()
+            .into_iter()
+            .all(|vert_id| hull.position(vert_id) == sphere.position(*origins.get_or_panic(vert_id)))
+    );
+    let (hull, _) = Mesh::<TestMesh>::platonic(Platonic::Dodecahedron, 1.).convex_hull(false).unwrap();
+    assert!(hull.nr_faces() == 36);
+    let (hull, _) = Mesh::<TestMesh>::cylinder(1., 1., 32).convex_hull(true).unwrap();
+    assert!(hull.nr_verts() == 64 && hull.nr_faces() == 34 && hull.is_polygonal().is_ok());
+
+    // Coplanar points have a flat, double-sided hull.
+    let points = (0..5)
+        .flat_map(|i| (0..5).map(move |j| (i, j)))
+        .map(|(i, j)| Vector3D::new(f64::from(i), f64::from(j), 2.))
+        .collect::<Vec<_>>();
+    for merge_coplanar in [false, true] {
+        let (hull, _) = Mesh::<TestMesh>::from_convex_hull(&points, merge_coplanar).unwrap();
+        assert!(hull.nr_verts() == 4 && euler(&hull) == 2 && hull.nr_faces() == if merge_coplanar { 2 } else { 4 });
+        assert!((hull.surface_area() - 32.).abs() < 1e-12 && hull.volume().abs() < 1e-12);
+    }
+
+    // Degenerate inputs.
+    assert!(Mesh::<TestMesh>::from_convex_hull(&[], false).is_err());
+    assert!(Mesh::<TestMesh>::from_convex_hull(&[Vector3D::new(1., 2., 3.); 4], false).is_err());
+    assert!(Mesh::<TestMesh>::from_convex_hull(&(0..5).map(|i| Vector3D::repeat(f64::from(i))).collect::<Vec<_>>(), true).is_err());
+}
+
+#[test]
+fn sampling() {
+    let sphere = sphere(16, 32);
+    let samples = sphere.sample_uniform(1000, 7);
+    assert!(samples.len() == 1000 && samples == sphere.sample_uniform(1000, 7) && samples != sphere.sample_uniform(1000, 8));
+    for sample in &samples {
+        let corners = sphere.vertices(sample.point.face);
+        let position = (0..3)
+            .map(|i| sphere.position(corners[sample.point.corners[i]]) * sample.point.barycentric[i])
+            .sum::<Vector3D>();
+        assert!((position - sample.point.position).norm() < 1e-12 && sample.point.barycentric.min() >= 0.);
+        assert!((sample.normal.norm() - 1.).abs() < 1e-12 && sample.normal.dot(&sample.point.position.normalize()) > 0.99);
+    }
+
+    // Samples are distributed by area: the faces of the cube have equal area.
+    let cube = cube();
+    let count = |samples: &[Sample<TestMesh>], face_id| samples.iter().filter(|sample| sample.point.face == face_id).count();
+    let uniform = cube.sample_uniform(6000, 1);
+    assert!(cube.face_ids().into_iter().all(|face_id| (800..1200).contains(&count(&uniform, face_id))));
+    let stratified = cube.sample_stratified(600, 1);
+    assert!(cube.face_ids().into_iter().all(|face_id| count(&stratified, face_id) == 100));
+
+    // Poisson-disk samples are at least the radius apart, and (almost) no point of the surface is farther than the radius from a sample.
+    for metric in [Metric::Euclidean, Metric::Geodesic] {
+        let samples = sphere.sample_poisson_disk(0.2, metric, 3);
+        let arc = |a: &Sample<TestMesh>, b: &Sample<TestMesh>| match metric {
+            Metric::Euclidean => (a.point.position - b.point.position).norm(),
+            Metric::Geodesic => a.point.position.angle(&b.point.position),
+        };
+        for (i, a) in samples.iter().enumerate() {
+            assert!(samples[i + 1..].iter().all(|b| arc(a, b) >= 0.2 - 1e-3), "{metric:?}");
+        }
+        let points = sphere.sample_uniform(1000, 4);
+        let covered = points
+            .iter()
+            .filter(|p| samples.iter().any(|s| (p.point.position - s.point.position).norm() <= 0.2))
+            .count() as Float
+            / 1000.;
+        assert!(covered > 0.99 && samples.len() > 150, "{metric:?}: {covered} {}", samples.len());
+    }
+
+    // Farthest-point samples are spread out over the vertices.
+    let samples = sphere.farthest_point_samples(2, Metric::Euclidean, 5);
+    assert!((samples[0].1.point.position - samples[1].1.point.position).norm() > 1.9);
+    for metric in [Metric::Euclidean, Metric::Geodesic] {
+        let samples = sphere.farthest_point_samples(20, metric, 5);
+        assert!(samples.iter().map(|(vert_id, _)| vert_id).collect::<std::collections::HashSet<_>>().len() == 20);
+        for (vert_id, sample) in &samples {
+            assert!(sphere.vertices(sample.point.face)[sample.point.corners[0]] == *vert_id && sample.point.position == sphere.position(*vert_id));
+        }
+        let spacing = samples
+            .iter()
+            .enumerate()
+            .flat_map(|(i, (_, a))| samples[i + 1..].iter().map(|(_, b)| (a.point.position - b.point.position).norm()));
+        assert!(spacing.fold(INF, Float::min) > 0.4, "{metric:?}");
+    }
+    assert!(cube.farthest_point_samples(20, Metric::Geodesic, 0).len() == 8);
+}
+
+#[test]
+fn deviations() {
+    // The same surface, with different faces.
+    let mut other = Mesh::<TestMesh>::cuboid(Vector3D::repeat(1.), true);
+    for vert_id in other.vert_ids() {
+        let position = other.position(vert_id) + Vector3D::repeat(0.5);
+        other.set_position(vert_id, position);
+    }
+    let deviation = cube().deviation(&other, 1000, 0);
+    assert!(deviation.max < 1e-12 && deviation.mean < 1e-12 && deviation.rms < 1e-12);
+    assert!(cube().hausdorff_distance(&other, 1000, 0) < 1e-12 && cube().chamfer_distance(&other, 1000, 0) < 1e-20);
+
+    // Concentric spheres deviate by the difference of their radii everywhere.
+    let (inner, outer) = (Mesh::<TestMesh>::icosphere(1., 3), Mesh::<TestMesh>::icosphere(1.1, 3));
+    let deviation = inner.deviation(&outer, 2000, 1);
+    assert!((deviation.max - 0.1).abs() < 0.01 && (deviation.mean - 0.1).abs() < 0.01 && deviation.mean <= deviation.rms && deviation.rms <= deviation.max);
+    assert!((inner.chamfer_distance(&outer, 2000, 1) - 0.02).abs() < 0.002);
+
+    // A sphere inside a box: the corners of the box are farthest from the sphere, but the sphere is closer to the box.
+    let (sphere, cuboid) = (Mesh::<TestMesh>::icosphere(0.5, 3), Mesh::<TestMesh>::cuboid(Vector3D::repeat(2.), false));
+    let (inward, outward) = (cuboid.deviation(&sphere, 2000, 2), sphere.deviation(&cuboid, 2000, 2));
+    assert!((inward.max - (3f64.sqrt() - 0.5)).abs() < 0.01 && (outward.max - (1. - 0.5 / 3f64.sqrt())).abs() < 0.01);
+    assert!(
+        cuboid
+            .vert_ids()
+            .into_iter()
+            .all(|vert_id| (inward.vertices.get_or_panic(vert_id) - inward.max).abs() < 0.01)
+    );
+    assert!((sphere.hausdorff_distance(&cuboid, 2000, 2) - inward.max).abs() < 1e-12);
+    assert!(sphere.deviation(&Mesh::<TestMesh>::default(), 10, 0).max == INF);
+}
+
+#[test]
+fn bounding_volumes() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let transformed = |mut mesh: Mesh<TestMesh>, transform: nalgebra::Isometry3<Float>| {
+        for vert_id in mesh.vert_ids() {
+            let position = transform * nalgebra::Point3::from(mesh.position(vert_id));
+            mesh.set_position(vert_id, position.coords);
+        }
+        mesh
+    };
+    let rotation = nalgebra::Isometry3::new(Vector3D::new(1., -2., 3.), Vector3D::new(0.3, -0.7, 1.1));
+
+    // A rotated box is its own bounding box, and a rotated cube too (but principal directions cannot find that).
+    let cuboid = transformed(Mesh::cuboid(Vector3D::new(1., 2., 3.), true), rotation);
+    let cube = transformed(Mesh::cuboid(Vector3D::repeat(1.), false), rotation);
+    for fit in [BoxFit::Pca, BoxFit::MinVolume] {
+        let obb = cuboid.oriented_bounding_box(fit);
+        assert!(
+            (obb.volume() - 6.).abs() < 1e-9 && (obb.half_extents - Vector3D::new(1.5, 1., 0.5)).norm() < 1e-9,
+            "{fit:?}"
+        );
+        assert!((obb.center - rotation.translation.vector).norm() < 1e-9);
+    }
+    let (pca, min_volume) = (cube.oriented_bounding_box(BoxFit::Pca), cube.oriented_bounding_box(BoxFit::MinVolume));
+    assert!((min_volume.volume() - 1.).abs() < 1e-9 && min_volume.volume() <= pca.volume() + 1e-12);
+
+    // The boxes contain the mesh, which their transformation maps into the axis-aligned box at the origin.
+    // The capsule is a 16-gon in cross-section, which fits best in a slightly rotated square.
+    let capsule = transformed(Mesh::capsule(0.5, 2., 16, 4), rotation);
+    for fit in [BoxFit::Pca, BoxFit::MinVolume] {
+        let obb = capsule.oriented_bounding_box(fit);
+        assert!(obb.volume() > 2.85 && obb.volume() < 3. + 1e-9, "{fit:?}: {}", obb.volume());
+        let canonical = obb.corners().map(|corner| (obb.to_canonical() * nalgebra::Point3::from(corner)).coords);
+        assert!(canonical.iter().all(|corner| (corner.abs() - obb.half_extents).norm() < 1e-9));
+        for vert_id in capsule.vert_ids() {
+            let local = (obb.to_canonical() * nalgebra::Point3::from(capsule.position(vert_id))).coords;
+            assert!((0..3).all(|axis| local[axis].abs() <= obb.half_extents[axis] + 1e-9));
+        }
+    }
+
+    // Minimal bounding spheres: of the cube through its corners, of the tetrahedron through its face opposite to the origin.
+    let ball = cube.bounding_sphere();
+    assert!((ball.center - rotation.translation.vector).norm() < 1e-9 && (ball.radius - 3f64.sqrt() / 2.).abs() < 1e-9);
+    let ball = tetrahedron().bounding_sphere();
+    assert!((ball.center - Vector3D::repeat(1. / 3.)).norm() < 1e-9 && (ball.radius - (2f64 / 3.).sqrt()).abs() < 1e-9);
+    let ball = capsule.bounding_sphere();
+    assert!((ball.center - rotation.translation.vector).norm() < 1e-9 && (ball.radius - 1.5).abs() < 1e-9);
+    let unit = ball.to_canonical();
+    let norms = capsule
+        .vert_ids()
+        .into_iter()
+        .map(|vert_id| (unit * nalgebra::Point3::from(capsule.position(vert_id))).coords.norm())
+        .collect::<Vec<_>>();
+    assert!(norms.iter().all(|&norm| norm <= 1. + 1e-9) && norms.iter().any(|&norm| norm > 1. - 1e-9));
+    let points = (0..200).map(|_| Vector3D::new(rng.random(), rng.random(), rng.random())).collect::<Vec<_>>();
+    let (hull, _) = Mesh::<TestMesh>::from_convex_hull(&points, false).unwrap();
+    let ball = hull.bounding_sphere();
+    assert!(points.iter().all(|p| (p - ball.center).norm() <= ball.radius + 1e-9));
+    assert!(
+        hull.vert_ids()
+            .into_iter()
+            .filter(|&vert_id| ((hull.position(vert_id) - ball.center).norm() - ball.radius).abs() < 1e-9)
+            .count()
+            >= 2
+    );
+}
+
+#[test]
+fn slicing() {
+    let z = Vector3D::z();
+    let signed_area = |contour: &[ContourPoint<TestMesh>]| {
+        (0..contour.len())
+            .map(|i| contour[i].position.cross(&contour[(i + 1) % contour.len()].position).dot(&z))
+            .sum::<Float>()
+            / 2.
+    };
+    let perimeter = |contour: &[ContourPoint<TestMesh>]| {
+        (0..contour.len())
+            .map(|i| (contour[(i + 1) % contour.len()].position - contour[i].position).norm())
+            .sum::<Float>()
+    };
+
+    // Every point lies on its edge, which crosses the plane from above to below.
+    let cube = cube();
+    let layers = cube.slices(z, &[0.25, 0.5, 0.75]);
+    for (layer, height) in layers.iter().zip([0.25, 0.5, 0.75]) {
+        assert!(layer.len() == 1 && layer[0].len() == 4 && (signed_area(&layer[0]) - 1.).abs() < 1e-12);
+        for point in &layer[0] {
+            let (root, toor) = (cube.position(cube.root(point.edge)), cube.position(cube.toor(point.edge)));
+            assert!(root.z >= height && toor.z < height && (0. ..1.).contains(&point.t));
+            assert!((root + (toor - root) * point.t - point.position).norm() < 1e-12 && (point.position.z - height).abs() < 1e-12);
+        }
+    }
+
+    // Vertices on the plane count as above it: the plane through the top face gives its boundary, and the plane through the bottom face nothing.
+    let top = cube.slice(Vector3D::new(0., 0., 1.), z);
+    assert!(top.len() == 1 && top[0].len() == 4 && top[0].iter().all(|point| point.t == 0.));
+    assert!(cube.slice(Vector3D::zeros(), z).is_empty() && cube.slice(Vector3D::new(0., 0., 2.), z).is_empty());
+    // A plane through a diagonal of the cube passes through two of its edges.
+    let diagonal = cube.slice(Vector3D::zeros(), Vector3D::new(1., -1., 0.));
+    assert!(diagonal.len() == 1 && (perimeter(&diagonal[0]) - 2. - 2. * 2f64.sqrt()).abs() < 1e-12);
+
+    // The torus has an outer (counterclockwise) and an inner (clockwise) contour in its equatorial plane.
+    let torus = Mesh::<TestMesh>::torus(2., 0.5, 32, 16);
+    let contours = torus.slice(Vector3D::zeros(), z);
+    let mut areas = contours.iter().map(|contour| signed_area(contour)).collect::<Vec<_>>();
+    areas.sort_by(Float::total_cmp);
+    assert!(contours.len() == 2 && areas[0] < 0. && areas[1] > 0.);
+    assert!((areas[0] + PI * 1.5 * 1.5).abs() < 0.1 && (areas[1] - PI * 2.5 * 2.5).abs() < 0.2);
+    assert!(torus.slice(Vector3D::zeros(), Vector3D::x()).len() == 2);
+
+    // Splitting gives two closed parts, with the cross-section as their caps.
+    let (above, below) = cube.split(Vector3D::new(0.3, 0.3, 0.3), Vector3D::new(1., 1., 1.)).unwrap();
+    assert!((above.volume() + below.volume() - 1.).abs() < 1e-12 && (below.volume() - 0.9f64.powi(3) / 6.).abs() < 1e-12);
+    let (above, below) = torus.split(Vector3D::zeros(), z).unwrap();
+    assert!((above.volume() - below.volume()).abs() < 1e-9 && (above.volume() + below.volume() - torus.volume()).abs() < 1e-9);
+    let euler_characteristic = |mesh: &Mesh<TestMesh>| mesh.nr_verts() as i64 - (mesh.nr_edges() / 2) as i64 + mesh.nr_faces() as i64;
+    assert!(euler_characteristic(&above) == 0 && euler_characteristic(&below) == 0);
+    // Splitting the torus through its hole gives two (topological) balls.
+    let (above, below) = torus.split(Vector3D::zeros(), Vector3D::x()).unwrap();
+    assert!(euler_characteristic(&above) == 2 && euler_characteristic(&below) == 2 && (above.volume() - below.volume()).abs() < 1e-9);
+
+    // A U-shaped prism, whose non-convex bottom and top faces are cut twice by the plane through both of its arms.
+    let outline = [(0., 0.), (3., 0.), (3., 3.), (2., 3.), (2., 1.), (1., 1.), (1., 3.), (0., 3.)];
+    let positions = [0., 1.].iter().flat_map(|&z| outline.map(|(x, y)| Vector3D::new(x, y, z))).collect::<Vec<_>>();
+    let mut faces = vec![(0..8).rev().collect::<Vec<_>>(), (8..16).collect()];
+    faces.extend((0..8).map(|i| vec![i, (i + 1) % 8, (i + 1) % 8 + 8, i + 8]));
+    let (prism, _, _) = Mesh::<TestMesh>::from(&faces, &positions).unwrap();
+    assert!((prism.volume() - 7.).abs() < 1e-12);
+    let contours = prism.slice(Vector3D::new(0., 2., 0.), Vector3D::y());
+    assert!(contours.len() == 2 && contours.iter().all(|contour| contour.len() == 4));
+    let (above, below) = prism.split(Vector3D::new(0., 2., 0.), Vector3D::y()).unwrap();
+    assert!((above.volume() - 2.).abs() < 1e-12 && (below.volume() - 5.).abs() < 1e-12);
+    assert!(euler_characteristic(&above) == 4 && euler_characteristic(&below) == 2);
+
+    // The plane through the top face leaves the cube below it, capped where the top face was, and nothing above it (and vice versa at the bottom face).
+    let (above, below) = cube.split(Vector3D::new(0., 0., 1.), z).unwrap();
+    assert!((below.volume() - 1.).abs() < 1e-12 && below.nr_faces() == 7 && above.nr_faces() == 0);
+    let (above, below) = cube.split(Vector3D::zeros(), z).unwrap();
+    assert!((above.volume() - 1.).abs() < 1e-12 && above.nr_faces() == 7 && below.nr_faces() == 0);
+    let (above, below) = sphere(8, 16).split(Vector3D::new(0., 0., -2.), z).unwrap();
+    assert!(below.nr_faces() == 0 && above.nr_faces() == sphere(8, 16).nr_faces());
+}
+
 #[cfg(feature = "stl")]
 #[test]
 fn from_blub_stl() {
@@ -98,3 +1064,324 @@ fn from_tetrahedron_obj() {
         }
     }
 }
+
+#[cfg(feature = "obj")]
+#[test]
+fn obj_roundtrip() {
+    let douconel = cube();
+    let path = std::env::temp_dir().join("mehsh_cube.obj");
+    assert!(douconel.to_obj(&path).is_ok());
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(contents.lines().filter(|line| line.starts_with("vn ")).count() == 8);
+    assert!(
+        contents
+            .lines()
+            .filter(|line| line.starts_with("f "))
+            .all(|line| line.matches("//").count() == 4)
+    );
+
+    let roundtrip = Mesh::<TestMesh>::from_obj(&path);
+    assert!(roundtrip.is_ok(), "{roundtrip:?}");
+    if let Ok((roundtrip, _, _)) = roundtrip {
+        assert!(roundtrip.nr_verts() == 8);
+        assert!(roundtrip.nr_faces() == 6);
+    }
+}
+
+#[cfg(feature = "gltf")]
+#[test]
+fn gltf_roundtrip() {
+    let douconel = tetrahedron();
+    let colors = douconel.face_ids().into_iter().map(|face_id| (face_id, [1., 0., 0.])).collect();
+
+    for extension in ["gltf", "glb"] {
+        let path = std::env::temp_dir().join(format!("mehsh_tetrahedron.{extension}"));
+        assert!(douconel.to_gltf(&path, GltfColors::PerFace(&colors), None).is_ok());
+
+        let roundtrip = Mesh::<TestMesh>::from_gltf(&path);
+        assert!(roundtrip.is_ok(), "{roundtrip:?}");
+        if let Ok((roundtrip, _, _)) = roundtrip {
+            assert!(roundtrip.nr_verts() == 4);
+            assert!(roundtrip.nr_edges() == 6 * 2);
+            assert!(roundtrip.nr_faces() == 4);
+        }
+    }
+}
+
+#[cfg(feature = "vtk")]
+#[test]
+fn vtk_export() {
+    let douconel = tetrahedron();
+    let defects = douconel.vert_attribute(|vert_id| douconel.defect(vert_id));
+    let normals = douconel.face_attribute(|face_id| douconel.normal(face_id));
+    let data = VtkData::new()
+        .with_point_scalars("angle defect", &defects)
+        .with_cell_vectors("normal", &normals);
+
+    let path = std::env::temp_dir().join("mehsh_tetrahedron.vtk");
+    assert!(douconel.to_vtk(&path, &data).is_ok());
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(contents.contains("POINTS 4 double"));
+    assert!(contents.contains("POLYGONS 4 16"));
+    assert!(contents.contains("SCALARS angle_defect double 1"));
+    assert!(contents.contains("VECTORS normal double"));
+
+    for (path, piece) in [
+        ("mehsh_tetrahedron.vtu", "NumberOfCells=\"4\""),
+        ("mehsh_tetrahedron.vtp", "NumberOfPolys=\"4\""),
+    ] {
+        let path = std::env::temp_dir().join(path);
+        assert!(
+            if piece.contains("Cells") {
+                douconel.to_vtu(&path, &data)
+            } else {
+                douconel.to_vtp(&path, &data)
+            }
+            .is_ok()
+        );
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.contains(piece));
+        assert!(contents.contains("Name=\"angle_defect\" NumberOfComponents=\"1\""));
+        assert!(contents.contains("Name=\"normal\" NumberOfComponents=\"3\""));
+    }
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn svg_export() {
+    let douconel = cube();
+    let fills = douconel.face_attribute(|_| [0.8, 0.2, 0.2]);
+    let labels = douconel.face_attribute(|face_id| format!("{face_id:?}"));
+    let top = douconel
+        .face_ids()
+        .into_iter()
+        .filter(|&face_id| douconel.normal(face_id).z > 0.5)
+        .collect::<Vec<_>>();
+    let svg = Svg::new(SvgLayout::Plane(Vector3D::z()))
+        .with_faces(top.clone())
+        .with_fills(&fills)
+        .with_face_labels(&labels)
+        .with_path(douconel.edges(top[0]), [0., 0., 1.], 2.);
+
+    let path = std::env::temp_dir().join("mehsh_cube_top.svg");
+    assert!(douconel.to_svg(&path, &svg).is_ok());
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(contents.matches("<polygon").count() == 1);
+    assert!(contents.matches("<line").count() == 4);
+    assert!(contents.matches("<text").count() == 1);
+    assert!(contents.contains("rgb(204,51,51)"));
+    assert!(contents.contains("<path d=\"M"));
+
+    // Every face as a separate piece, at true size.
+    let pieces = douconel.face_attribute(|face_id| douconel.project(face_id));
+    let svg = Svg::new(SvgLayout::Pieces(&pieces)).with_scale(10.);
+    let path = std::env::temp_dir().join("mehsh_cube_pieces.svg");
+    assert!(douconel.to_svg(&path, &svg).is_ok());
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(contents.matches("<polygon").count() == 6);
+    assert!(contents.contains("mm\""));
+}
+
+#[cfg(feature = "medit")]
+#[test]
+fn medit_roundtrip() {
+    let douconel = cube();
+    let mut labels = Labels::new();
+    for (i, face_id) in douconel.face_ids().into_iter().enumerate() {
+        labels.faces.insert(face_id, i as i64 + 1);
+    }
+    for edge_id in douconel.edge_ids() {
+        let [a, b] = douconel.vertices(edge_id)[..] else { unreachable!() };
+        labels.add_ridge(&douconel, a, b);
+    }
+    labels.corners.extend(douconel.vert_ids());
+
+    let path = std::env::temp_dir().join("mehsh_cube.mesh");
+    assert!(douconel.to_medit(&path, &labels).is_ok());
+    let roundtrip = Mesh::<TestMesh>::from_medit(&path);
+    assert!(roundtrip.is_ok(), "{roundtrip:?}");
+    if let Ok((roundtrip, _, _, roundtrip_labels)) = roundtrip {
+        assert!(roundtrip.nr_verts() == 8);
+        assert!(roundtrip.nr_faces() == 6);
+        assert!(roundtrip_labels.ridges.len() == 12 * 2);
+        assert!(roundtrip_labels.corners.len() == 8);
+        let mut tags = roundtrip
+            .face_ids()
+            .iter()
+            .map(|&face_id| *roundtrip_labels.faces.get(face_id).unwrap())
+            .collect::<Vec<_>>();
+        tags.sort_unstable();
+        assert!(tags == vec![1, 2, 3, 4, 5, 6]);
+    }
+}
+
+#[cfg(feature = "gmsh")]
+#[test]
+fn gmsh_roundtrip() {
+    let douconel = cube();
+    let mut labels = Labels::new();
+    for face_id in douconel.face_ids() {
+        labels.faces.insert(face_id, 7);
+    }
+    labels.corners.insert(douconel.vert_ids()[0]);
+
+    let path = std::env::temp_dir().join("mehsh_cube.msh");
+    assert!(douconel.to_gmsh(&path, &labels).is_ok());
+    let roundtrip = Mesh::<TestMesh>::from_gmsh(&path);
+    assert!(roundtrip.is_ok(), "{roundtrip:?}");
+    if let Ok((roundtrip, _, _, roundtrip_labels)) = roundtrip {
+        assert!(roundtrip.nr_verts() == 8);
+        assert!(roundtrip.nr_faces() == 6);
+        assert!(roundtrip_labels.corners.len() == 1);
+        assert!(roundtrip.face_ids().iter().all(|&face_id| roundtrip_labels.faces.get(face_id) == Some(&7)));
+    }
+}
+
+#[cfg(feature = "gmsh")]
+#[test]
+fn from_gmsh_v4() {
+    let contents = "$MeshFormat\n4.1 0 8\n$EndMeshFormat\n\
+        $Entities\n0 0 1 0\n1 0 0 0 1 1 1 1 3 0\n$EndEntities\n\
+        $Nodes\n1 4 1 4\n2 1 0 4\n1\n2\n3\n4\n0 0 0\n1 0 0\n0 1 0\n0 0 1\n$EndNodes\n\
+        $Elements\n1 4 1 4\n2 1 2 4\n1 1 3 2\n2 1 2 4\n3 2 3 4\n4 1 4 3\n$EndElements\n";
+    let path = std::env::temp_dir().join("mehsh_tetrahedron_v4.msh");
+    std::fs::write(&path, contents).unwrap();
+    let douconel = Mesh::<TestMesh>::from_gmsh(&path);
+    assert!(douconel.is_ok(), "{douconel:?}");
+    if let Ok((douconel, _, _, labels)) = douconel {
+        assert!(douconel.nr_verts() == 4);
+        assert!(douconel.nr_faces() == 4);
+        assert!(douconel.face_ids().iter().all(|&face_id| labels.faces.get(face_id) == Some(&3)));
+    }
+}
+
+#[test]
+fn mehsh_roundtrip() {
+    // Splitting removes and reuses elements, such that the keys are not contiguous.
+    let mut douconel = tetrahedron();
+    let edge_id = douconel.edge_ids()[0];
+    douconel.split_edge(edge_id);
+
+    let mut attributes = Attributes::default();
+    attributes.verts.insert(
+        "height".to_owned(),
+        Attribute::Scalar(douconel.vert_attribute(|vert_id| douconel.position(vert_id).z)),
+    );
+    attributes.faces.insert(
+        "normal".to_owned(),
+        Attribute::Vector(douconel.face_attribute(|face_id| douconel.normal(face_id))),
+    );
+
+    for compress in [false, true] {
+        let options = NativeOptions {
+            compress,
+            face_location: true,
+            vert_location: true,
+        };
+        let path = std::env::temp_dir().join(format!("mehsh_tetrahedron_{compress}.mehsh"));
+        assert!(douconel.to_mehsh(&path, &attributes, options).is_ok());
+        let roundtrip = Mesh::<TestMesh>::from_mehsh(&path);
+        assert!(roundtrip.is_ok(), "{roundtrip:?}");
+        if let Ok(file) = roundtrip {
+            assert!(file.mesh.vert_ids() == douconel.vert_ids());
+            assert!(file.mesh.edge_ids() == douconel.edge_ids());
+            assert!(file.mesh.face_ids() == douconel.face_ids());
+            for edge_id in douconel.edge_ids() {
+                assert!(file.mesh.twin(edge_id) == douconel.twin(edge_id));
+                assert!(file.mesh.next(edge_id) == douconel.next(edge_id));
+                assert!(file.mesh.root(edge_id) == douconel.root(edge_id));
+            }
+            for vert_id in douconel.vert_ids() {
+                assert!(file.mesh.position(vert_id) == douconel.position(vert_id));
+                let Some(Attribute::Scalar(height)) = file.attributes.verts.get("height") else {
+                    panic!()
+                };
+                assert!(height.get(vert_id) == Some(&douconel.position(vert_id).z));
+            }
+            assert!(matches!(file.attributes.faces.get("normal"), Some(Attribute::Vector(_))));
+
+            let face_id = douconel.face_ids()[0];
+            let location = file.face_location.unwrap();
+            assert!(location.nearest(&douconel.position(face_id).into()) == face_id);
+            assert!(file.vert_location.is_some());
+        }
+    }
+
+    let path = std::env::temp_dir().join("mehsh_invalid.mehsh");
+    std::fs::write(&path, b"MEHSH\0\x63\0\0\0\0\0").unwrap();
+    assert!(Mesh::<TestMesh>::from_mehsh(&path).is_err());
+
+    // Removing elements leaves free slots, which are stored such that the keys of the remaining elements can be restored.
+    let cube = cube();
+    let faces = cube.face_ids().into_iter().map(|face_id| cube.vertices(face_id)).collect::<Vec<_>>();
+    let index_of = cube
+        .vert_ids()
+        .into_iter()
+        .enumerate()
+        .map(|(i, vert_id)| (vert_id, i))
+        .collect::<std::collections::HashMap<_, _>>();
+    let faces = faces
+        .iter()
+        .map(|face| face.iter().map(|vert_id| index_of[vert_id]).collect::<Vec<_>>())
+        .flat_map(|face| [face.clone(), face.iter().map(|i| i + 8).collect()])
+        .collect::<Vec<_>>();
+    let positions = [0., 2.]
+        .iter()
+        .flat_map(|&offset| cube.vert_ids().into_iter().map(move |vert_id| (vert_id, offset)))
+        .map(|(vert_id, offset)| cube.position(vert_id) + Vector3D::new(offset, 0., 0.))
+        .collect::<Vec<_>>();
+    let (mut cubes, _, _) = Mesh::<TestMesh>::from(&faces, &positions).unwrap();
+    for edge_id in cubes.edge_ids() {
+        if cubes.position(cubes.root(edge_id)).x < 1.5 {
+            cubes.faces.remove(cubes.face(edge_id));
+            cubes.edges.remove(edge_id);
+        }
+    }
+    for vert_id in cubes.vert_ids() {
+        if cubes.position(vert_id).x < 1.5 {
+            cubes.verts.remove(vert_id);
+        }
+    }
+    assert!(cubes.nr_verts() == 8 && cubes.nr_edges() == 24 && cubes.nr_faces() == 6);
+    let path = std::env::temp_dir().join("mehsh_removed.mehsh");
+    assert!(cubes.to_mehsh(&path, &Attributes::default(), NativeOptions::default()).is_ok());
+    let file = Mesh::<TestMesh>::from_mehsh(&path).unwrap();
+    assert!(file.mesh.vert_ids() == cubes.vert_ids() && file.mesh.edge_ids() == cubes.edge_ids() && file.mesh.face_ids() == cubes.face_ids());
+    assert!(file.mesh.verts.free_slots() == cubes.verts.free_slots() && (file.mesh.volume() - 1.).abs() < 1e-12);
+
+    // Corrupt keys (of the first vertex, after the header and the number of vertices) are rejected without reproducing them:
+    // a slot index beyond the number of slots, an unoccupied (even) version, and the key of the second vertex.
+    let path = std::env::temp_dir().join("mehsh_corrupt.mehsh");
+    assert!(douconel.to_mehsh(&path, &Attributes::default(), NativeOptions::default()).is_ok());
+    let bytes = std::fs::read(&path).unwrap();
+    for key in [0xffff_ffff | 1 << 32, 1 | 2 << 32, u64::from_le_bytes(bytes[60..68].try_into().unwrap())] {
+        let mut corrupt = bytes.clone();
+        corrupt[20..28].copy_from_slice(&u64::to_le_bytes(key));
+        std::fs::write(&path, &corrupt).unwrap();
+        assert!(Mesh::<TestMesh>::from_mehsh(&path).is_err());
+    }
+
+    // A corrupt face location (the left child of its root) is rejected before it is used: a cycle back to the root, and a node out of range.
+    let options = NativeOptions {
+        face_location: true,
+        ..NativeOptions::default()
+    };
+    assert!(douconel.to_mehsh(&path, &Attributes::default(), options).is_ok());
+    let bytes = std::fs::read(&path).unwrap();
+    let elements = |nr: usize, size: usize, nr_free: usize| 8 + nr * size + 8 + nr_free * 8;
+    let nodes = 12
+        + elements(douconel.nr_verts(), 40, douconel.verts.free_slots().len())
+        + elements(douconel.nr_edges(), 41, douconel.edges.free_slots().len())
+        + elements(douconel.nr_faces(), 17, douconel.faces.free_slots().len())
+        + 3 * 8;
+    assert!(bytes[nodes + 8] == 1);
+    for child in [0, u64::MAX >> 1] {
+        let mut corrupt = bytes.clone();
+        corrupt[nodes + 17..nodes + 25].copy_from_slice(&u64::to_le_bytes(child));
+        std::fs::write(&path, &corrupt).unwrap();
+        assert!(Mesh::<TestMesh>::from_mehsh(&path).is_err());
+    }
+    std::fs::write(&path, &bytes).unwrap();
+    assert!(Mesh::<TestMesh>::from_mehsh(&path).is_ok());
+    assert!(ids::IdxMap::<VERT, TestMesh, ()>::from_entries(vec![(ids::Key::from_bits(1 | 3 << 32), ())], &[]).is_some());
+}
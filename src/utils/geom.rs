@@ -1,4 +1,5 @@
 use crate::utils::primitives::{EPS, Vector2D, Vector3D};
+use robust::{Coord, Coord3D};
 
 /// Represents the orientation of three points in 3D space.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -15,28 +16,106 @@ pub enum IntersectionType {
     Endpoint,
 }
 
+// Robust geometric predicates (adaptive precision, Shewchuk-style). Only the sign of the result is exact.
+
+// Positive if `a`, `b` and `c` are in counterclockwise order, negative if clockwise, and zero if collinear.
 #[must_use]
-pub fn calculate_triangle_area(t: (Vector3D, Vector3D, Vector3D)) -> f64 {
-    (t.1 - t.0).cross(&(t.2 - t.0)).magnitude() * 0.5
+pub fn orient2d(a: Vector2D, b: Vector2D, c: Vector2D) -> f64 {
+    robust::orient2d(coord(a), coord(b), coord(c))
 }
 
+// Positive if `d` lies above the plane through `a`, `b` and `c` (on the side of the normal (b - a) x (c - a)), negative if below, and zero if coplanar.
 #[must_use]
-pub fn are_points_coplanar(a: Vector3D, b: Vector3D, c: Vector3D, d: Vector3D) -> bool {
-    (b - a).cross(&(c - a)).dot(&(d - a)) == 0.
+pub fn orient3d(a: Vector3D, b: Vector3D, c: Vector3D, d: Vector3D) -> f64 {
+    -robust::orient3d(coord3d(a), coord3d(b), coord3d(c), coord3d(d))
 }
 
+// Positive if `d` lies inside the circle through `a`, `b` and `c`, negative if outside, and zero if cocircular (or if `a`, `b` and `c` are collinear).
 #[must_use]
-pub fn calculate_orientation(a: Vector3D, b: Vector3D, c: Vector3D, n: Vector3D) -> Orientation {
-    let orientation = (b - a).cross(&(c - a)).dot(&n);
-    if orientation > 0. {
+pub fn incircle(a: Vector2D, b: Vector2D, c: Vector2D, d: Vector2D) -> f64 {
+    robust::incircle(coord(a), coord(b), coord(c), coord(d)) * sign(orient2d(a, b, c))
+}
+
+// Positive if `e` lies inside the sphere through `a`, `b`, `c` and `d`, negative if outside, and zero if cospherical (or if `a`, `b`, `c` and `d` are coplanar).
+#[must_use]
+pub fn insphere(a: Vector3D, b: Vector3D, c: Vector3D, d: Vector3D, e: Vector3D) -> f64 {
+    robust::insphere(coord3d(a), coord3d(b), coord3d(c), coord3d(d), coord3d(e)) * sign(-orient3d(a, b, c, d))
+}
+
+#[must_use]
+pub fn orientation(a: Vector2D, b: Vector2D, c: Vector2D) -> Orientation {
+    to_orientation(orient2d(a, b, c))
+}
+
+// Tolerance for constructed (rounded) quantities, relative to the magnitude `scale` of the coordinates involved.
+#[must_use]
+pub fn tolerance(scale: f64) -> f64 {
+    64. * EPS * scale
+}
+
+// Largest absolute coordinate of the given points, to be used as scale for `tolerance`.
+#[must_use]
+pub fn scale<'a>(points: impl IntoIterator<Item = &'a Vector3D>) -> f64 {
+    points.into_iter().map(|p| p.abs().max()).fold(0., f64::max)
+}
+
+// Axis along which the given normal is largest. Dropping this axis gives the projection to 2D with the least distortion.
+#[must_use]
+pub fn dominant_axis(normal: Vector3D) -> usize {
+    normal.abs().imax()
+}
+
+// Project a point to 2D by dropping an axis. The remaining axes are taken in cyclic order, such that orientations are preserved
+// when looking at the plane from the positive side of the dropped axis (and reversed when looking from the negative side).
+#[must_use]
+pub fn drop_axis(point: Vector3D, axis: usize) -> Vector2D {
+    Vector2D::new(point[(axis + 1) % 3], point[(axis + 2) % 3])
+}
+
+fn coord(p: Vector2D) -> Coord<f64> {
+    Coord { x: p.x, y: p.y }
+}
+
+fn coord3d(p: Vector3D) -> Coord3D<f64> {
+    Coord3D { x: p.x, y: p.y, z: p.z }
+}
+
+fn sign(value: f64) -> f64 {
+    if value > 0. {
+        1.
+    } else if value < 0. {
+        -1.
+    } else {
+        0.
+    }
+}
+
+fn to_orientation(value: f64) -> Orientation {
+    if value > 0. {
         Orientation::CCW
-    } else if orientation < 0. {
+    } else if value < 0. {
         Orientation::CW
     } else {
         Orientation::C
     }
 }
 
+#[must_use]
+pub fn calculate_triangle_area(t: (Vector3D, Vector3D, Vector3D)) -> f64 {
+    (t.1 - t.0).cross(&(t.2 - t.0)).magnitude() * 0.5
+}
+
+#[must_use]
+pub fn are_points_coplanar(a: Vector3D, b: Vector3D, c: Vector3D, d: Vector3D) -> bool {
+    orient3d(a, b, c, d) == 0.
+}
+
+#[must_use]
+pub fn calculate_orientation(a: Vector3D, b: Vector3D, c: Vector3D, n: Vector3D) -> Orientation {
+    // Orientation of the triangle, as seen from the side that `n` points to.
+    to_orientation(orient3d(a, b, c, a + n))
+}
+
 #[must_use]
 pub fn calculate_clockwise_angle(a: Vector3D, b: Vector3D, c: Vector3D, n: Vector3D) -> f64 {
     let ab = (b - a).normalize();
@@ -54,13 +133,21 @@ pub fn project_point_onto_plane(point: Vector3D, plane: (Vector3D, Vector3D), re
     Vector2D::new((point - reference).dot(&plane.0), (point - reference).dot(&plane.1))
 }
 
+// Whether `p` lies in triangle `t` (boundary included). The point should lie in the plane of the triangle, up to a tolerance relative to the coordinates.
 #[must_use]
 pub fn is_point_inside_triangle(p: Vector3D, t: (Vector3D, Vector3D, Vector3D)) -> bool {
-    let s1 = calculate_triangle_area((t.0, t.1, p));
-    let s2 = calculate_triangle_area((t.1, t.2, p));
-    let s3 = calculate_triangle_area((t.2, t.0, p));
-    let st = calculate_triangle_area(t);
-    (s1 + s2 + s3 - st).abs() < EPS && (0.0 - EPS..=st + EPS).contains(&s1) && (0.0 - EPS..=st + EPS).contains(&s2) && (0.0 - EPS..=st + EPS).contains(&s3)
+    let normal = (t.1 - t.0).cross(&(t.2 - t.0));
+    if normal == Vector3D::zeros() {
+        return false;
+    }
+    if (p - t.0).dot(&normal).abs() > tolerance(scale([&p, &t.0, &t.1, &t.2])) * normal.norm() {
+        return false;
+    }
+
+    let axis = dominant_axis(normal);
+    let (p, a, b, c) = (drop_axis(p, axis), drop_axis(t.0, axis), drop_axis(t.1, axis), drop_axis(t.2, axis));
+    let (o1, o2, o3) = (orient2d(a, b, p), orient2d(b, c, p), orient2d(c, a, p));
+    (o1 >= 0. && o2 >= 0. && o3 >= 0.) || (o1 <= 0. && o2 <= 0. && o3 <= 0.)
 }
 
 #[must_use]
@@ -68,46 +155,30 @@ pub fn is_within_inclusive_range(a: f64, b: f64, c: f64) -> bool {
     if b < c { (b..=c).contains(&a) } else { (c..=b).contains(&a) }
 }
 
+// Intersection of segments `p` and `q`. Parallel (and collinear) segments are considered to not intersect.
+// If an endpoint of one segment lies on the other segment, that endpoint is returned exactly.
 #[must_use]
 pub fn calculate_2d_lineseg_intersection(p_u: Vector2D, p_v: Vector2D, q_u: Vector2D, q_v: Vector2D) -> Option<(Vector2D, IntersectionType)> {
-    let (x1, x2, x3, x4, y1, y2, y3, y4) = (p_u.x, p_v.x, q_u.x, q_v.x, p_u.y, p_v.y, q_u.y, q_v.y);
+    // Sides of the endpoints of one segment, with respect to the other segment.
+    let (o_qu, o_qv) = (orient2d(p_u, p_v, q_u), orient2d(p_u, p_v, q_v));
+    let (o_pu, o_pv) = (orient2d(q_u, q_v, p_u), orient2d(q_u, q_v, p_v));
 
-    let t_numerator = (x1 - x3) * (y3 - y4) - (y1 - y3) * (x3 - x4);
-    let u_numerator = (x1 - x3) * (y1 - y2) - (y1 - y3) * (x1 - x2);
-    let denominator = (x1 - x2) * (y3 - y4) - (y1 - y2) * (x3 - x4);
-
-    if denominator.abs() < EPS {
+    if (o_qu == 0. && o_qv == 0.) || (o_pu == 0. && o_pv == 0.) {
+        return None;
+    }
+    if o_qu * o_qv > 0. || o_pu * o_pv > 0. {
         return None;
     }
 
-    if is_within_inclusive_range(t_numerator, 0.0, denominator) {
-        let t = t_numerator / denominator;
-        if t.abs() < EPS {
-            return Some((p_u, IntersectionType::Endpoint));
+    match (o_pu, o_pv, o_qu, o_qv) {
+        (0., _, _, _) => Some((p_u, IntersectionType::Endpoint)),
+        (_, 0., _, _) => Some((p_v, IntersectionType::Endpoint)),
+        (_, _, 0., _) => Some((q_u, IntersectionType::Endpoint)),
+        (_, _, _, 0.) => Some((q_v, IntersectionType::Endpoint)),
+        _ => {
+            let t = (o_pu / (o_pu - o_pv)).clamp(0., 1.);
+            Some((p_u + (p_v - p_u) * t, IntersectionType::Proper))
         }
-        if (t - 1.0).abs() < EPS {
-            return Some((p_v, IntersectionType::Endpoint));
-        }
-        let sx_t = t.mul_add(x2 - x1, x1);
-        let sy_t = t.mul_add(y2 - y1, y1);
-        let s_t = Vector2D::new(sx_t, sy_t);
-
-        Some((s_t, IntersectionType::Proper))
-    } else if is_within_inclusive_range(u_numerator, 0.0, denominator) {
-        let u = u_numerator / denominator;
-        if u.abs() < EPS {
-            return Some((q_u, IntersectionType::Endpoint));
-        }
-        if (u - 1.0).abs() < EPS {
-            return Some((q_v, IntersectionType::Endpoint));
-        }
-        let sx_u = u.mul_add(x4 - x3, x3);
-        let sy_u = u.mul_add(y4 - y3, y3);
-        let s_u = Vector2D::new(sx_u, sy_u);
-
-        Some((s_u, IntersectionType::Proper))
-    } else {
-        None
     }
 }
 
@@ -117,57 +188,225 @@ pub fn calculate_3d_lineseg_intersection(p_u: Vector3D, p_v: Vector3D, q_u: Vect
         return None;
     }
 
-    let p = p_v - p_u;
-    let q = q_v - q_u;
-    let normal_vector = p.cross(&q).normalize();
-    let reference_point = p_u;
-    let plane = (p.normalize(), p.cross(&normal_vector).normalize());
+    // Exactly coplanar, so the segments can be projected by dropping the axis along which their common plane is largest.
+    let normal = (p_v - p_u).cross(&(q_v - q_u));
+    let normal = if normal == Vector3D::zeros() {
+        (p_v - p_u).cross(&(q_u - p_u))
+    } else {
+        normal
+    };
+    if normal == Vector3D::zeros() {
+        return None;
+    }
+    let axis = dominant_axis(normal);
 
-    calculate_2d_lineseg_intersection(
-        project_point_onto_plane(p_u, plane, reference_point),
-        project_point_onto_plane(p_v, plane, reference_point),
-        project_point_onto_plane(q_u, plane, reference_point),
-        project_point_onto_plane(q_v, plane, reference_point),
+    calculate_2d_lineseg_intersection(drop_axis(p_u, axis), drop_axis(p_v, axis), drop_axis(q_u, axis), drop_axis(q_v, axis)).map(
+        |(point_in_2d, intersection_type)| {
+            let point_in_3d = match intersection_type {
+                IntersectionType::Endpoint => [p_u, p_v, q_u, q_v].into_iter().find(|&p| drop_axis(p, axis) == point_in_2d).unwrap_or(p_u),
+                IntersectionType::Proper => {
+                    // Recover the parameter along `p` from the projected coordinate in which the segment is longest.
+                    let (u, v) = (drop_axis(p_u, axis), drop_axis(p_v, axis));
+                    let i = (v - u).abs().imax();
+                    p_u + (p_v - p_u) * ((point_in_2d[i] - u[i]) / (v[i] - u[i]))
+                }
+            };
+            (point_in_3d, intersection_type)
+        },
     )
-    .map(|(point_in_2d, intersection_type)| {
-        let point_in_3d = reference_point + (plane.0 * point_in_2d.x) + (plane.1 * point_in_2d.y);
-        (point_in_3d, intersection_type)
-    })
 }
 
-/// Calculates the distance of point `p` to triangle `t`
+// Intersection of the ray `origin + t * direction` (t >= 0) with triangle `t`, from either side, as (t, barycentric coordinates of the corners).
+// Watertight (Woop et al., 2013): a ray through a shared edge or vertex of a closed mesh hits at least one of the triangles around it.
 #[must_use]
-pub fn distance_to_triangle(p: Vector3D, t: (Vector3D, Vector3D, Vector3D)) -> f64 {
+pub fn ray_triangle_intersection(origin: Vector3D, direction: Vector3D, t: (Vector3D, Vector3D, Vector3D)) -> Option<(f64, Vector3D)> {
+    // Transform to a frame in which the ray points along the (dominant) z-axis, with a shear such that its direction becomes (0, 0, 1).
+    let kz = dominant_axis(direction);
+    if direction[kz] == 0. {
+        return None;
+    }
+    let (kx, ky) = if direction[kz] > 0. {
+        ((kz + 1) % 3, (kz + 2) % 3)
+    } else {
+        ((kz + 2) % 3, (kz + 1) % 3)
+    };
+    let shear = Vector3D::new(direction[kx] / direction[kz], direction[ky] / direction[kz], 1. / direction[kz]);
+    let transform = |p: Vector3D| {
+        let p = p - origin;
+        (Vector2D::new(shear.x.mul_add(-p[kz], p[kx]), shear.y.mul_add(-p[kz], p[ky])), shear.z * p[kz])
+    };
+    let ((a, az), (b, bz), (c, cz)) = (transform(t.0), transform(t.1), transform(t.2));
+
+    // Edge functions (signed areas with the ray) are evaluated exactly, so that neighboring triangles agree on them.
+    let o = Vector2D::zeros();
+    let (u, v, w) = (orient2d(o, b, c), orient2d(o, c, a), orient2d(o, a, b));
+    if (u < 0. || v < 0. || w < 0.) && (u > 0. || v > 0. || w > 0.) {
+        return None;
+    }
+    let determinant = u + v + w;
+    if determinant == 0. {
+        return None;
+    }
+    let distance = (u * az + v * bz + w * cz) / determinant;
+    (distance >= 0.).then(|| (distance, Vector3D::new(u, v, w) / determinant))
+}
+
+// Closest point to `p` on triangle `t` (Ericson, Real-Time Collision Detection, 5.1.5), as (point, barycentric coordinates of the corners).
+#[must_use]
+pub fn closest_point_on_triangle(p: Vector3D, t: (Vector3D, Vector3D, Vector3D)) -> (Vector3D, Vector3D) {
     let (a, b, c) = t;
+    let (ab, ac, ap) = (b - a, c - a, p - a);
 
-    let ab = b - a;
-    let ac = c - a;
-    let ap = p - a;
+    // Degenerate triangles (segments or points) have no interior, only edges.
+    if ab.cross(&ac) == Vector3D::zeros() {
+        return [(a, b, 0), (b, c, 1), (c, a, 2)]
+            .into_iter()
+            .map(|(u, v, i)| {
+                let s = closest_parameter_on_segment(p, u, v);
+                let mut barycentric = Vector3D::zeros();
+                barycentric[i] = 1. - s;
+                barycentric[(i + 1) % 3] = s;
+                (u + (v - u) * s, barycentric)
+            })
+            .min_by(|x, y| (x.0 - p).norm_squared().total_cmp(&(y.0 - p).norm_squared()))
+            .unwrap();
+    }
 
-    // Normal vector of the triangle plane
-    let n = ab.cross(&ac);
-    let n_norm = n.norm();
-    let n_unit = n / n_norm;
+    // Vertex region of a
+    let (d1, d2) = (ab.dot(&ap), ac.dot(&ap));
+    if d1 <= 0. && d2 <= 0. {
+        return (a, Vector3D::new(1., 0., 0.));
+    }
+    // Vertex region of b
+    let bp = p - b;
+    let (d3, d4) = (ab.dot(&bp), ac.dot(&bp));
+    if d3 >= 0. && d4 <= d3 {
+        return (b, Vector3D::new(0., 1., 0.));
+    }
+    // Edge region of ab
+    let vc = d1.mul_add(d4, -d3 * d2);
+    if vc <= 0. && d1 >= 0. && d3 <= 0. {
+        let v = d1 / (d1 - d3);
+        return (a + ab * v, Vector3D::new(1. - v, v, 0.));
+    }
+    // Vertex region of c
+    let cp = p - c;
+    let (d5, d6) = (ab.dot(&cp), ac.dot(&cp));
+    if d6 >= 0. && d5 <= d6 {
+        return (c, Vector3D::new(0., 0., 1.));
+    }
+    // Edge region of ac
+    let vb = d5.mul_add(d2, -d1 * d6);
+    if vb <= 0. && d2 >= 0. && d6 <= 0. {
+        let w = d2 / (d2 - d6);
+        return (a + ac * w, Vector3D::new(1. - w, 0., w));
+    }
+    // Edge region of bc
+    let va = d3.mul_add(d6, -d5 * d4);
+    if va <= 0. && (d4 - d3) >= 0. && (d5 - d6) >= 0. {
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return (b + (c - b) * w, Vector3D::new(0., 1. - w, w));
+    }
+    // Face region
+    let denominator = 1. / (va + vb + vc);
+    let (v, w) = (vb * denominator, vc * denominator);
+    (a + ab * v + ac * w, Vector3D::new(1. - v - w, v, w))
+}
 
-    // Distance from p to the plane
-    let dist_to_plane = ap.dot(&n_unit);
-    let proj = p - dist_to_plane * n_unit;
+// Intersection of two triangles (see `triangle_triangle_intersection`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TriangleIntersection {
+    // The triangles cross (or touch) along a segment, which is a single point if its endpoints are equal.
+    Segment(Vector3D, Vector3D),
+    // The triangles are coplanar, and overlap (or touch).
+    Coplanar,
+}
+
+// Intersection of triangles `t` and `u` (boundaries included). Degenerate triangles (segments or points) never intersect.
+// Which corners lie on which side of the plane of the other triangle is decided exactly, such that separated triangles are never reported.
+#[must_use]
+pub fn triangle_triangle_intersection(t: (Vector3D, Vector3D, Vector3D), u: (Vector3D, Vector3D, Vector3D)) -> Option<TriangleIntersection> {
+    let (t, u) = ([t.0, t.1, t.2], [u.0, u.1, u.2]);
+    let (t_normal, u_normal) = ((t[1] - t[0]).cross(&(t[2] - t[0])), (u[1] - u[0]).cross(&(u[2] - u[0])));
+    if t_normal == Vector3D::zeros() || u_normal == Vector3D::zeros() {
+        return None;
+    }
+
+    // Signed distances (up to a factor) of the corners of each triangle to the plane of the other.
+    let t_distances = t.map(|p| orient3d(u[0], u[1], u[2], p));
+    let u_distances = u.map(|p| orient3d(t[0], t[1], t[2], p));
+    let separated = |distances: [f64; 3]| distances.iter().all(|&d| d > 0.) || distances.iter().all(|&d| d < 0.);
+    if separated(t_distances) || separated(u_distances) {
+        return None;
+    }
+    if u_distances == [0.; 3] || t_distances == [0.; 3] {
+        return coplanar_triangles_overlap(t, u, dominant_axis(t_normal)).then_some(TriangleIntersection::Coplanar);
+    }
 
-    if n_norm != 0.0 && is_point_inside_triangle(proj, (a, b, c)) {
-        dist_to_plane.abs() // Perpendicular distance
+    // Both triangles cross the line where the planes meet in a segment, and the triangles intersect where these segments overlap.
+    let direction = t_normal.cross(&u_normal);
+    let crossing = |p: [Vector3D; 3], distances: [f64; 3]| {
+        let mut points = vec![];
+        for i in 0..3 {
+            let (d_i, d_j) = (distances[i], distances[(i + 1) % 3]);
+            if d_i == 0. {
+                points.push(p[i]);
+            } else if (d_i > 0. && d_j < 0.) || (d_i < 0. && d_j > 0.) {
+                points.push(p[i] + (p[(i + 1) % 3] - p[i]) * (d_i / (d_i - d_j)));
+            }
+        }
+        let parameter = |q: &&Vector3D| q.dot(&direction);
+        let start = *points.iter().min_by(|a, b| parameter(a).total_cmp(&parameter(b))).unwrap();
+        let end = *points.iter().max_by(|a, b| parameter(a).total_cmp(&parameter(b))).unwrap();
+        (start, end)
+    };
+    let (t_segment, u_segment) = (crossing(t, t_distances), crossing(u, u_distances));
+    let start = if t_segment.0.dot(&direction) >= u_segment.0.dot(&direction) {
+        t_segment.0
+    } else {
+        u_segment.0
+    };
+    let end = if t_segment.1.dot(&direction) <= u_segment.1.dot(&direction) {
+        t_segment.1
     } else {
-        // Closest distance to one of the triangle’s edges
-        let d1 = distance_to_segment(p, a, b);
-        let d2 = distance_to_segment(p, b, c);
-        let d3 = distance_to_segment(p, c, a);
-        d1.min(d2).min(d3)
+        u_segment.1
+    };
+    (start.dot(&direction) <= end.dot(&direction)).then_some(TriangleIntersection::Segment(start, end))
+}
+
+// Whether coplanar triangles `t` and `u` overlap (boundaries included), after projecting them to 2D by dropping `axis`.
+fn coplanar_triangles_overlap(t: [Vector3D; 3], u: [Vector3D; 3], axis: usize) -> bool {
+    let (t, u) = (t.map(|p| drop_axis(p, axis)), u.map(|p| drop_axis(p, axis)));
+    let inside = |p: Vector2D, t: [Vector2D; 3]| {
+        let (o1, o2, o3) = (orient2d(t[0], t[1], p), orient2d(t[1], t[2], p), orient2d(t[2], t[0], p));
+        (o1 >= 0. && o2 >= 0. && o3 >= 0.) || (o1 <= 0. && o2 <= 0. && o3 <= 0.)
+    };
+    let edges_cross = (0..3).any(|i| (0..3).any(|j| segments_intersect(t[i], t[(i + 1) % 3], u[j], u[(j + 1) % 3])));
+    edges_cross || inside(t[0], u) || inside(u[0], t)
+}
+
+// Whether segments `ab` and `cd` intersect (endpoints and collinear overlaps included).
+fn segments_intersect(a: Vector2D, b: Vector2D, c: Vector2D, d: Vector2D) -> bool {
+    let (o_c, o_d) = (orient2d(a, b, c), orient2d(a, b, d));
+    let (o_a, o_b) = (orient2d(c, d, a), orient2d(c, d, b));
+    if o_c == 0. && o_d == 0. {
+        // Collinear: the segments overlap if their bounding boxes do.
+        return (0..2).all(|i| a[i].min(b[i]) <= c[i].max(d[i]) && c[i].min(d[i]) <= a[i].max(b[i]));
     }
+    !(o_c > 0. && o_d > 0. || o_c < 0. && o_d < 0. || o_a > 0. && o_b > 0. || o_a < 0. && o_b < 0.)
 }
 
-fn distance_to_segment(p: Vector3D, a: Vector3D, b: Vector3D) -> f64 {
+/// Calculates the distance of point `p` to triangle `t`
+#[must_use]
+pub fn distance_to_triangle(p: Vector3D, t: (Vector3D, Vector3D, Vector3D)) -> f64 {
+    (p - closest_point_on_triangle(p, t).0).norm()
+}
+
+// Parameter (in [0, 1]) of the closest point to `p` on segment `ab`.
+fn closest_parameter_on_segment(p: Vector3D, a: Vector3D, b: Vector3D) -> f64 {
     let ab = b - a;
-    let t = (p - a).dot(&ab) / ab.dot(&ab);
-    let t_clamped = t.clamp(0.0, 1.0);
-    let closest = a + ab * t_clamped;
-    (p - closest).norm()
+    if ab == Vector3D::zeros() {
+        return 0.;
+    }
+    ((p - a).dot(&ab) / ab.dot(&ab)).clamp(0., 1.)
 }
@@ -0,0 +1,91 @@
+use crate::utils::primitives::Vector3D;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// Dense 3D array of values sampled on a regular grid. Sample (i, j, k) lies at `origin + (i, j, k) * spacing` (component-wise),
+// and is stored at index i + j * nx + k * nx * ny, where `resolution` = [nx, ny, nz] is the number of samples along each axis.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Grid<T> {
+    pub origin: Vector3D,
+    pub spacing: Vector3D,
+    pub resolution: [usize; 3],
+    pub values: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    // Sample `f` on a grid spanning the box from `min` to `max` (both included), with the given number of samples along each axis.
+    // With the `rayon` feature, the samples are evaluated in parallel.
+    #[must_use]
+    pub fn from_fn(bbox: (Vector3D, Vector3D), resolution: [usize; 3], f: impl Fn(Vector3D) -> T + Sync) -> Self
+    where
+        T: Send,
+    {
+        let (min, max) = bbox;
+        let spacing = Vector3D::from_fn(|axis, _| {
+            if resolution[axis] > 1 {
+                (max[axis] - min[axis]) / (resolution[axis] - 1) as f64
+            } else {
+                0.
+            }
+        });
+        // The layout of the grid, without values, which can be shared between threads.
+        let layout = Grid::<()> {
+            origin: min,
+            spacing,
+            resolution,
+            values: vec![],
+        };
+        let nr_samples = resolution.iter().product::<usize>();
+        #[cfg(feature = "rayon")]
+        let values = (0..nr_samples)
+            .into_par_iter()
+            .map(|index| f(layout.position(layout.coordinates(index))))
+            .collect();
+        #[cfg(not(feature = "rayon"))]
+        let values = (0..nr_samples).map(|index| f(layout.position(layout.coordinates(index)))).collect();
+        Self {
+            origin: min,
+            spacing,
+            resolution,
+            values,
+        }
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    // Index of sample (i, j, k) in `values`.
+    #[must_use]
+    pub const fn index(&self, [i, j, k]: [usize; 3]) -> usize {
+        i + self.resolution[0] * (j + self.resolution[1] * k)
+    }
+
+    // Inverse of `index`.
+    #[must_use]
+    pub const fn coordinates(&self, index: usize) -> [usize; 3] {
+        let [nx, ny, _] = self.resolution;
+        [index % nx, index / nx % ny, index / (nx * ny)]
+    }
+
+    // Position of sample (i, j, k).
+    #[must_use]
+    pub fn position(&self, [i, j, k]: [usize; 3]) -> Vector3D {
+        self.origin + self.spacing.component_mul(&Vector3D::new(i as f64, j as f64, k as f64))
+    }
+
+    #[must_use]
+    pub fn get(&self, coordinates: [usize; 3]) -> Option<&T> {
+        if (0..3).any(|axis| coordinates[axis] >= self.resolution[axis]) {
+            return None;
+        }
+        self.values.get(self.index(coordinates))
+    }
+}
@@ -2,6 +2,8 @@ use bimap::BiHashMap;
 use serde::Deserialize;
 use serde::Serialize;
 use slotmap::DefaultKey;
+use slotmap::Key as _;
+use slotmap::KeyData;
 use slotmap::SecondaryMap;
 use slotmap::SlotMap;
 use std::hash::Hash;
@@ -47,6 +49,17 @@ impl<K, M> Key<K, M> {
     pub const fn raw(self) -> DefaultKey {
         self.raw
     }
+
+    // Stable 64-bit representation of the key (slot index and version), used for storing keys on disk.
+    #[must_use]
+    pub fn to_bits(self) -> u64 {
+        self.raw.data().as_ffi()
+    }
+
+    #[must_use]
+    pub fn from_bits(bits: u64) -> Self {
+        Self::new(KeyData::from_ffi(bits).into())
+    }
 }
 
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
@@ -68,6 +81,63 @@ impl<K, M, V> IdxMap<K, M, V> {
         Key::new(self.map.insert(value))
     }
 
+    // Indices of the free slots below the highest occupied one, which are needed to restore the keys (see `IdxMap::from_entries`).
+    #[must_use]
+    pub fn free_slots(&self) -> Vec<usize> {
+        free_slots(self.ids())
+    }
+
+    // Build a map in which every value keeps its given key, where the keys and the free slots (see `IdxMap::free_slots`) together fill
+    // the slots from 1 up to their number. Slots are filled in order, and a slot is freed and reused until it reaches the version of its key
+    // (so this takes time linear in the versions, which is the number of times the slots were reused).
+    // Returns None if the keys cannot be reproduced (duplicate or invalid keys, or keys and free slots that do not fill the slots).
+    #[must_use]
+    pub fn from_entries(entries: Vec<(Key<K, M>, V)>, free_slots: &[usize]) -> Option<Self>
+    where
+        V: Clone,
+    {
+        let mut map = Self::new();
+        let Some(placeholder) = entries.first().map(|(_, value)| value.clone()) else {
+            return Some(map);
+        };
+
+        // A key is stored as (version << 32) | index, where slot indices start at 1 and occupied slots have an odd version.
+        let entries = entries.into_iter().map(|(key, value)| (key.to_bits(), value)).collect::<Vec<_>>();
+        let index = |bits: u64| (bits & 0xffff_ffff) as usize;
+        let version = |bits: u64| bits >> 32;
+
+        let nr_slots = entries.len() + free_slots.len();
+        let mut filled = vec![false; nr_slots];
+        for slot in entries.iter().map(|&(bits, _)| index(bits)).chain(free_slots.iter().copied()) {
+            if slot == 0 || slot > nr_slots || std::mem::replace(&mut filled[slot - 1], true) {
+                return None;
+            }
+        }
+        if entries.iter().any(|&(bits, _)| version(bits) % 2 == 0) {
+            return None;
+        }
+
+        let mut slots = (0..nr_slots).map(|_| map.map.insert(placeholder.clone())).collect::<Vec<_>>();
+        for (bits, value) in entries {
+            let current = &mut slots[index(bits) - 1];
+            while current.data().as_ffi() != bits {
+                if version(current.data().as_ffi()) >= version(bits) {
+                    return None;
+                }
+                // Every other slot is occupied, so the freed slot is reused immediately.
+                map.map.remove(*current);
+                *current = map.map.insert(placeholder.clone());
+            }
+            map.map[*current] = value;
+        }
+
+        for &slot in free_slots {
+            map.map.remove(slots[slot - 1]);
+        }
+
+        Some(map)
+    }
+
     pub fn remove(&mut self, key: Key<K, M>) -> bool {
         self.map.remove(key.raw()).is_some()
     }
@@ -106,6 +176,17 @@ impl<K, M, V> IdxMap<K, M, V> {
     }
 }
 
+// Indices of the free slots below the highest slot of the keys (see `IdxMap::free_slots`).
+pub fn free_slots<K, M>(keys: impl IntoIterator<Item = Key<K, M>>) -> Vec<usize> {
+    let occupied = keys
+        .into_iter()
+        .map(|key| (key.to_bits() & 0xffff_ffff) as usize)
+        .collect::<std::collections::HashSet<_>>();
+    (1..=occupied.iter().copied().max().unwrap_or_default())
+        .filter(|slot| !occupied.contains(slot))
+        .collect()
+}
+
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
 pub struct AssMap<K1, K2, M> {
     map: SecondaryMap<DefaultKey, DefaultKey>,
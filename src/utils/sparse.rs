@@ -0,0 +1,190 @@
+use crate::utils::primitives::Float;
+
+// Sparse matrix in triplet (coordinate) form, for assembly. Duplicate entries are summed when converting to CSR.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Triplets {
+    pub nrows: usize,
+    pub ncols: usize,
+    pub entries: Vec<(usize, usize, Float)>,
+}
+
+impl Triplets {
+    #[must_use]
+    pub const fn new(nrows: usize, ncols: usize) -> Self {
+        Self { nrows, ncols, entries: vec![] }
+    }
+
+    pub fn push(&mut self, row: usize, col: usize, value: Float) {
+        assert!(row < self.nrows && col < self.ncols, "({row}, {col}) is out of bounds");
+        self.entries.push((row, col, value));
+    }
+
+    // Convert to CSR, with sorted column indices and duplicate entries summed.
+    #[must_use]
+    pub fn to_csr(&self) -> Csr {
+        let mut entries = self.entries.clone();
+        entries.sort_by_key(|&(row, col, _)| (row, col));
+
+        let mut csr = Csr {
+            nrows: self.nrows,
+            ncols: self.ncols,
+            offsets: vec![0; self.nrows + 1],
+            indices: vec![],
+            values: vec![],
+        };
+        let mut last = None;
+        for (row, col, value) in entries {
+            if last == Some((row, col)) {
+                *csr.values.last_mut().unwrap() += value;
+            } else {
+                csr.indices.push(col);
+                csr.values.push(value);
+                csr.offsets[row + 1] += 1;
+                last = Some((row, col));
+            }
+        }
+        for row in 0..self.nrows {
+            csr.offsets[row + 1] += csr.offsets[row];
+        }
+        csr
+    }
+}
+
+// Sparse matrix in compressed sparse row (CSR) form. The column indices of row `i` are `indices[offsets[i]..offsets[i + 1]]` (sorted).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Csr {
+    pub nrows: usize,
+    pub ncols: usize,
+    pub offsets: Vec<usize>,
+    pub indices: Vec<usize>,
+    pub values: Vec<Float>,
+}
+
+impl Csr {
+    // Diagonal matrix with the given values.
+    #[must_use]
+    pub fn diagonal(values: &[Float]) -> Self {
+        Self {
+            nrows: values.len(),
+            ncols: values.len(),
+            offsets: (0..=values.len()).collect(),
+            indices: (0..values.len()).collect(),
+            values: values.to_vec(),
+        }
+    }
+
+    // Number of stored entries.
+    #[must_use]
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    // Stored entries as (row, col, value), row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, Float)> + '_ {
+        (0..self.nrows).flat_map(move |row| (self.offsets[row]..self.offsets[row + 1]).map(move |i| (row, self.indices[i], self.values[i])))
+    }
+
+    // Value at (row, col), zero if not stored.
+    #[must_use]
+    pub fn get(&self, row: usize, col: usize) -> Float {
+        let range = self.offsets[row]..self.offsets[row + 1];
+        self.indices[range.clone()].binary_search(&col).map_or(0., |i| self.values[range.start + i])
+    }
+
+    #[must_use]
+    pub fn transpose(&self) -> Self {
+        let mut triplets = Triplets::new(self.ncols, self.nrows);
+        triplets.entries = self.iter().map(|(row, col, value)| (col, row, value)).collect();
+        triplets.to_csr()
+    }
+
+    // Matrix-vector product.
+    #[must_use]
+    pub fn mul_vec(&self, x: &[Float]) -> Vec<Float> {
+        assert_eq!(x.len(), self.ncols, "dimension mismatch");
+        (0..self.nrows)
+            .map(|row| (self.offsets[row]..self.offsets[row + 1]).map(|i| self.values[i] * x[self.indices[i]]).sum())
+            .collect()
+    }
+
+    // Matrix-matrix product.
+    #[must_use]
+    pub fn mul(&self, other: &Self) -> Self {
+        assert_eq!(self.ncols, other.nrows, "dimension mismatch");
+        let mut triplets = Triplets::new(self.nrows, other.ncols);
+        for (row, k, a) in self.iter() {
+            for i in other.offsets[k]..other.offsets[k + 1] {
+                triplets.entries.push((row, other.indices[i], a * other.values[i]));
+            }
+        }
+        triplets.to_csr()
+    }
+
+    // Solve A x = b with the conjugate gradient method, for symmetric positive definite A (for example L + M, or L with a constrained vertex).
+    // Returns None if the relative residual is not below `tolerance` within `max_iterations`.
+    #[must_use]
+    pub fn conjugate_gradient(&self, b: &[Float], tolerance: Float, max_iterations: usize) -> Option<Vec<Float>> {
+        let dot = |u: &[Float], v: &[Float]| u.iter().zip(v).map(|(a, b)| a * b).sum::<Float>();
+        let norm = dot(b, b).sqrt();
+        let mut x = vec![0.; self.ncols];
+        let mut residual = b.to_vec();
+        let mut direction = residual.clone();
+        let mut rr = dot(&residual, &residual);
+        for _ in 0..max_iterations {
+            if rr.sqrt() <= tolerance * norm {
+                return Some(x);
+            }
+            let ad = self.mul_vec(&direction);
+            let alpha = rr / dot(&direction, &ad);
+            for i in 0..x.len() {
+                x[i] += alpha * direction[i];
+                residual[i] -= alpha * ad[i];
+            }
+            let next = dot(&residual, &residual);
+            for i in 0..direction.len() {
+                direction[i] = residual[i] + next / rr * direction[i];
+            }
+            rr = next;
+        }
+        (rr.sqrt() <= tolerance * norm).then_some(x)
+    }
+
+    // Sum of two matrices of the same dimensions.
+    #[must_use]
+    pub fn add(&self, other: &Self) -> Self {
+        assert_eq!((self.nrows, self.ncols), (other.nrows, other.ncols), "dimension mismatch");
+        let mut triplets = Triplets::new(self.nrows, self.ncols);
+        triplets.entries = self.iter().chain(other.iter()).collect();
+        triplets.to_csr()
+    }
+
+    // Multiply every entry by a scalar.
+    #[must_use]
+    pub fn scale(mut self, factor: Float) -> Self {
+        self.values.iter_mut().for_each(|value| *value *= factor);
+        self
+    }
+}
+
+#[cfg(feature = "nalgebra-sparse")]
+impl From<&Csr> for nalgebra_sparse::CsrMatrix<Float> {
+    fn from(csr: &Csr) -> Self {
+        Self::try_from_csr_data(csr.nrows, csr.ncols, csr.offsets.clone(), csr.indices.clone(), csr.values.clone()).expect("valid CSR data")
+    }
+}
+
+#[cfg(feature = "nalgebra-sparse")]
+impl From<&Triplets> for nalgebra_sparse::CooMatrix<Float> {
+    fn from(triplets: &Triplets) -> Self {
+        let (rows, cols, values) = triplets
+            .entries
+            .iter()
+            .fold((vec![], vec![], vec![]), |(mut rows, mut cols, mut values), &(row, col, value)| {
+                rows.push(row);
+                cols.push(col);
+                values.push(value);
+                (rows, cols, values)
+            });
+        Self::try_from_triplets(triplets.nrows, triplets.ncols, rows, cols, values).expect("valid triplets")
+    }
+}